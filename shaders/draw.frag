@@ -5,7 +5,10 @@ in VS_OUT
     vec3 color;
 } fs_in;
 
+// The knot's material (see `Material` in the knot module)
+uniform vec3 u_base_color = vec3(1.0);
+
 void main()
 {
-    gl_FragColor = vec4(fs_in.color, 1.0);
+    gl_FragColor = vec4(fs_in.color * u_base_color, 1.0);
 }
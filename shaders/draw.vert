@@ -17,6 +17,9 @@ uniform mat4 u_model;
 uniform mat4 u_view;
 uniform mat4 u_projection;
 
+// Set to `1.0` to use the per-vertex `color` attribute instead of the default coloring
+uniform float u_use_vertex_colors = 0.0;
+
 uniform uint u_number_of_beads = 10; // TODO
 
 const float pi = 3.1415926535897932384626433832795;
@@ -39,6 +42,7 @@ void main()
     vec3 world_space_modified = abs(position / 4.0) * 0.5 + 0.5;
     world_space_modified.z = sqrt(world_space_modified.z * 6.0);
     vs_out.color = hsv_to_rgb(vec3(world_space_modified.zyx) * vec3(0.89, 0.6, 1.0));
+    vs_out.color = mix(vs_out.color, color, u_use_vertex_colors);
 
     // Apply MVP matrices
     vec4 m_space = u_model * vec4(position, 1.0);
@@ -0,0 +1,101 @@
+use cgmath::Vector3;
+
+/// The color stops of matplotlib's "viridis" colormap, sampled at nine evenly spaced points.
+const VIRIDIS: [(f32, [f32; 3]); 9] = [
+    (0.000, [0.267, 0.004, 0.329]),
+    (0.125, [0.278, 0.176, 0.482]),
+    (0.250, [0.231, 0.322, 0.545]),
+    (0.375, [0.173, 0.447, 0.557]),
+    (0.500, [0.129, 0.569, 0.549]),
+    (0.625, [0.157, 0.682, 0.502]),
+    (0.750, [0.369, 0.788, 0.384]),
+    (0.875, [0.678, 0.863, 0.188]),
+    (1.000, [0.992, 0.906, 0.145]),
+];
+
+/// The color stops of the classic "jet" colormap (dark blue, blue, cyan, yellow, red, dark red).
+const JET: [(f32, [f32; 3]); 6] = [
+    (0.000, [0.0, 0.0, 0.5]),
+    (0.125, [0.0, 0.0, 1.0]),
+    (0.375, [0.0, 1.0, 1.0]),
+    (0.625, [1.0, 1.0, 0.0]),
+    (0.875, [1.0, 0.0, 0.0]),
+    (1.000, [0.5, 0.0, 0.0]),
+];
+
+/// Linearly interpolates between the two color stops in `table` that surround `t`. The stops
+/// must be sorted, with the first at `0.0` and the last at `1.0`.
+fn lookup(table: &[(f32, [f32; 3])], t: f32) -> Vector3<f32> {
+    let t = t.clamp(0.0, 1.0);
+
+    for pair in table.windows(2) {
+        let ((start, a), (end, b)) = (pair[0], pair[1]);
+        if t <= end {
+            let pct = (t - start) / (end - start);
+            return Vector3::new(
+                a[0] + (b[0] - a[0]) * pct,
+                a[1] + (b[1] - a[1]) * pct,
+                a[2] + (b[2] - a[2]) * pct,
+            );
+        }
+    }
+
+    let [r, g, b] = table[table.len() - 1].1;
+    Vector3::new(r, g, b)
+}
+
+/// Maps `t` (clamped to the range `[0, 1]`) to the perceptually uniform "viridis" colormap,
+/// which runs from dark purple to yellow.
+pub fn viridis(t: f32) -> Vector3<f32> {
+    lookup(&VIRIDIS, t)
+}
+
+/// Maps `t` (clamped to the range `[0, 1]`) to the "jet" colormap, which runs from dark blue
+/// to dark red through cyan and yellow.
+pub fn jet(t: f32) -> Vector3<f32> {
+    lookup(&JET, t)
+}
+
+/// Maps `t` (clamped to the range `[0, 1]`) to a shade of gray, from black to white.
+pub fn grayscale(t: f32) -> Vector3<f32> {
+    let t = t.clamp(0.0, 1.0);
+    Vector3::new(t, t, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EPSILON;
+    use crate::utils::approx_eq_vec3;
+
+    #[test]
+    fn endpoints_match_the_first_and_last_stops() {
+        assert_eq!(viridis(0.0), Vector3::new(0.267, 0.004, 0.329));
+        assert_eq!(viridis(1.0), Vector3::new(0.992, 0.906, 0.145));
+        assert_eq!(jet(0.0), Vector3::new(0.0, 0.0, 0.5));
+        assert_eq!(jet(1.0), Vector3::new(0.5, 0.0, 0.0));
+        assert_eq!(grayscale(1.0), Vector3::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        assert_eq!(viridis(-1.0), viridis(0.0));
+        assert_eq!(viridis(2.0), viridis(1.0));
+        assert_eq!(jet(2.0), jet(1.0));
+        assert_eq!(grayscale(-0.5), Vector3::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn colors_are_interpolated_between_stops() {
+        assert!(approx_eq_vec3(
+            &jet(0.25),
+            &Vector3::new(0.0, 0.5, 1.0),
+            EPSILON
+        ));
+        assert!(approx_eq_vec3(
+            &viridis(0.0625),
+            &Vector3::new(0.2725, 0.09, 0.4055),
+            EPSILON
+        ));
+    }
+}
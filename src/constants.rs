@@ -1,4 +1,14 @@
 pub const WIDTH: u32 = 612;
 pub const HEIGHT: u32 = 460;
 pub const EPSILON: f32 = 0.001;
-pub const MOUSE_SENSITIVITY: f32 = 3.0;
+pub const ZOOM_SENSITIVITY: f32 = 2.0;
+pub const DEFAULT_CAMERA_DISTANCE: f32 = 45.0;
+pub const MIN_CAMERA_DISTANCE: f32 = 5.0;
+pub const MAX_CAMERA_DISTANCE: f32 = 200.0;
+pub const LAYOUT_GAP: f32 = 5.0;
+pub const TUBE_RADIUS: f32 = 0.5;
+pub const TUBE_SEGMENTS: usize = 12;
+pub const STEPS_PER_SECOND: f32 = 60.0;
+pub const MAX_TIME_STEP: f32 = 2.0;
+pub const FRAME_DIRECTORY: &str = "frames";
+pub const FRAME_STRIDE: usize = 2;
@@ -0,0 +1,848 @@
+use crate::constants;
+
+use cgmath::{InnerSpace, Vector3, Zero};
+use graphics_utils::polyline::Polyline;
+use std::f32::consts;
+use std::io;
+use std::path::Path;
+
+/// Additional operations on the polylines from `graphics_utils`, which treat each polyline
+/// as a closed loop (like the rope of a knot).
+pub trait PolylineExt {
+    /// Inserts the vertex `v` at position `index`, shifting every vertex after it forwards.
+    /// Panics if `index` is greater than the number of vertices.
+    fn insert_vertex(&mut self, index: usize, v: &Vector3<f32>);
+
+    /// Reverses the order of the vertices in place, which reverses the orientation of the
+    /// curve (and so changes the sign of oriented invariants, such as the linking number).
+    fn reverse(&mut self);
+
+    /// Returns the signed area enclosed by the projection of this (closed) polyline onto the
+    /// xy-plane, computed with the shoelace formula. The area is positive if the projection
+    /// winds counter-clockwise and negative if it winds clockwise.
+    fn signed_area_xy(&self) -> f32;
+
+    /// Returns the (discrete) curvature of this (closed) polyline at the vertex at `index`,
+    /// i.e. the angle between its two adjacent segments divided by the average length of
+    /// those segments.
+    fn curvature_at(&self, index: usize) -> f32;
+
+    /// Returns the total curvature of this (closed) polyline: the sum of the angles through
+    /// which the curve turns at each of its vertices. By Fenchel's theorem, this is at least
+    /// `2π`, with equality only for convex planar curves (and it exceeds `4π` for a knot).
+    fn total_curvature(&self) -> f32;
+
+    /// Returns a copy of this (closed) polyline with as few vertices as possible, such that
+    /// every removed vertex lies within `tolerance` of the simplified curve, using the
+    /// Ramer-Douglas-Peucker algorithm. The loop is split into two open chains at the first
+    /// vertex and the vertex farthest from it, both of which are always kept.
+    fn simplify(&self, tolerance: f32) -> Polyline;
+
+    /// Returns an orthonormal frame `(tangent, normal, binormal)` at each vertex of this
+    /// (closed) polyline. The tangent at each vertex points from its previous neighbor to its
+    /// next neighbor. The first normal points towards the center of curvature (or in some
+    /// perpendicular direction, where the curve is straight), and every other normal is
+    /// parallel transported from the vertex before it: the previous normal is projected onto
+    /// the plane perpendicular to the new tangent, so the frame twists as little as possible.
+    /// The binormal is `tangent × normal`.
+    ///
+    /// Transporting the last normal back to the first vertex generally doesn't reproduce the
+    /// first normal (the frame picks up a twist, or holonomy, that depends on the shape of the
+    /// curve), which would leave a seam in anything swept along the frames. To close the
+    /// seam, each frame is rotated about its tangent by a share of the opposite angle that is
+    /// proportional to its arc length from the first vertex.
+    fn frames(&self) -> Vec<(Vector3<f32>, Vector3<f32>, Vector3<f32>)>;
+
+    /// Sweeps a circle with `segments` points around each vertex of this (closed) polyline,
+    /// in the plane spanned by the normal and binormal returned by `frames`, and stitches
+    /// consecutive circles ("rings") together into a tube. The radius of the ring around
+    /// vertex `i` is `radius(i)`. Returns a list of outward-facing triangles (three vertices
+    /// each): see `tube_ring` for the ring that each vertex belongs to.
+    fn tube(&self, radius: impl Fn(usize) -> f32, segments: usize) -> Vec<Vector3<f32>>;
+
+    /// Sweeps a flat ribbon of the given `width` along this (closed) polyline: the two edges
+    /// of the ribbon are offset by `width / 2` on either side of each vertex, along the
+    /// binormal returned by `frames`. Returns a triangle strip that alternates between the two
+    /// edges, with two points per vertex (the first two are repeated at the end to close the
+    /// loop). Any twisting of the ribbon reflects the framing of the curve.
+    fn generate_ribbon(&self, width: f32) -> Vec<Vector3<f32>>;
+
+    /// Builds a closed polyline by sampling the curve `f` at `samples` evenly spaced points of
+    /// `[0, 1)` (the last sample is joined back to the first).
+    fn from_parametric(f: impl Fn(f32) -> Vector3<f32>, samples: usize) -> Self
+    where
+        Self: Sized;
+
+    /// Reads a closed curve from a Wavefront .obj file at `path`, such as one exported by
+    /// another modeling tool. Every `v` line is read as a vertex. If the file contains any `l`
+    /// (polyline) elements, they determine the order in which the vertices are connected:
+    /// otherwise, the vertices are connected in the order that they appear. The curve is
+    /// always closed, and a final vertex that repeats the first is dropped.
+    fn from_obj(path: &Path) -> io::Result<Self>
+    where
+        Self: Sized;
+
+    /// Samples the standard parametrization of the trefoil at `samples` points.
+    fn trefoil(samples: usize) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_parametric(
+            |s| {
+                let t = s * 2.0 * consts::PI;
+                Vector3::new(
+                    t.sin() + 2.0 * (2.0 * t).sin(),
+                    t.cos() - 2.0 * (2.0 * t).cos(),
+                    -(3.0 * t).sin(),
+                )
+            },
+            samples,
+        )
+    }
+
+    /// Samples the `(p, q)` torus knot at `samples` points: the curve winds `p` times around
+    /// the axis of symmetry of a torus and `q` times around its interior (`p` and `q` should be
+    /// coprime, otherwise the curve retraces itself).
+    fn torus_knot(p: usize, q: usize, samples: usize) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_parametric(
+            |s| {
+                let t = s * 2.0 * consts::PI;
+                let (p, q) = (p as f32, q as f32);
+                let r = (q * t).cos() + 2.0;
+                Vector3::new(r * (p * t).cos(), r * (p * t).sin(), -(q * t).sin())
+            },
+            samples,
+        )
+    }
+}
+
+impl PolylineExt for Polyline {
+    fn insert_vertex(&mut self, index: usize, v: &Vector3<f32>) {
+        let mut vertices = self.get_vertices().clone();
+        vertices.insert(index, *v);
+        self.set_vertices(&vertices);
+    }
+
+    fn reverse(&mut self) {
+        let mut vertices = self.get_vertices().clone();
+        vertices.reverse();
+        self.set_vertices(&vertices);
+    }
+
+    fn signed_area_xy(&self) -> f32 {
+        let vertices = self.get_vertices();
+        let n = vertices.len();
+        let twice_area: f32 = (0..n)
+            .map(|i| {
+                let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        0.5 * twice_area
+    }
+
+    fn curvature_at(&self, index: usize) -> f32 {
+        let (angle, length) = turning_angle(self, index);
+        if length < constants::EPSILON {
+            return 0.0;
+        }
+        angle / length
+    }
+
+    fn total_curvature(&self) -> f32 {
+        (0..self.get_number_of_vertices())
+            .map(|index| turning_angle(self, index).0)
+            .sum()
+    }
+
+    fn simplify(&self, tolerance: f32) -> Polyline {
+        let vertices = self.get_vertices();
+        let n = vertices.len();
+        let mut simplified = Polyline::new();
+        if n <= 3 {
+            simplified.set_vertices(vertices);
+            return simplified;
+        }
+
+        // Walk the loop from the first vertex, around to the farthest vertex, and back again
+        // (the first vertex is repeated at the end)
+        let farthest = (1..n)
+            .max_by(|i, j| {
+                let di = (vertices[*i] - vertices[0]).magnitude2();
+                let dj = (vertices[*j] - vertices[0]).magnitude2();
+                di.partial_cmp(&dj).unwrap()
+            })
+            .unwrap();
+        let mut keep = vec![false; n + 1];
+        keep[0] = true;
+        keep[farthest] = true;
+        keep[n] = true;
+
+        let unrolled: Vec<Vector3<f32>> =
+            vertices.iter().chain(vertices.first()).cloned().collect();
+        douglas_peucker(&unrolled, 0, farthest, tolerance, &mut keep);
+        douglas_peucker(&unrolled, farthest, n, tolerance, &mut keep);
+
+        for index in 0..n {
+            if keep[index] {
+                simplified.push_vertex(&vertices[index]);
+            }
+        }
+        simplified
+    }
+
+    fn frames(&self) -> Vec<(Vector3<f32>, Vector3<f32>, Vector3<f32>)> {
+        let vertices = self.get_vertices();
+        let n = vertices.len();
+        if n < 2 {
+            return vec![(Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()); n];
+        }
+
+        let tangents: Vec<Vector3<f32>> = (0..n)
+            .map(|i| {
+                let tangent = vertices[(i + 1) % n] - vertices[(i + n - 1) % n];
+                if tangent.magnitude() < constants::EPSILON {
+                    Vector3::unit_x()
+                } else {
+                    tangent.normalize()
+                }
+            })
+            .collect();
+
+        // Start with the direction that the curve bends towards at the first vertex
+        let incoming = vertices[0] - vertices[n - 1];
+        let outgoing = vertices[1 % n] - vertices[0];
+        let mut normal = if incoming.magnitude() > constants::EPSILON
+            && outgoing.magnitude() > constants::EPSILON
+        {
+            outgoing.normalize() - incoming.normalize()
+        } else {
+            Vector3::zero()
+        };
+        normal = normal - tangents[0] * normal.dot(tangents[0]);
+        if normal.magnitude() < constants::EPSILON {
+            normal = perpendicular(&tangents[0]);
+        }
+        normal = normal.normalize();
+
+        let mut frames = Vec::with_capacity(n);
+        for tangent in tangents.iter() {
+            let projected = normal - tangent * normal.dot(*tangent);
+            if projected.magnitude() > constants::EPSILON {
+                normal = projected.normalize();
+            } else {
+                // The tangent reversed direction: any perpendicular direction will do
+                normal = perpendicular(tangent);
+            }
+            frames.push((*tangent, normal, tangent.cross(normal)));
+        }
+
+        // Find the angle between the first normal and the last normal, transported back to
+        // the first vertex (measured about the first tangent)
+        let (tangent, first_normal, first_binormal) = frames[0];
+        let (_, last_normal, _) = frames[n - 1];
+        let closing = last_normal - tangent * last_normal.dot(tangent);
+        if closing.magnitude() < constants::EPSILON {
+            return frames;
+        }
+        let holonomy = closing.dot(first_binormal).atan2(closing.dot(first_normal));
+
+        // Undo the twist gradually, by arc length
+        let mut arc_lengths = vec![0.0; n];
+        for i in 1..n {
+            arc_lengths[i] = arc_lengths[i - 1] + (vertices[i] - vertices[i - 1]).magnitude();
+        }
+        let total_length = arc_lengths[n - 1] + (vertices[0] - vertices[n - 1]).magnitude();
+
+        for ((tangent, normal, binormal), arc_length) in frames.iter_mut().zip(arc_lengths) {
+            let angle = -holonomy * arc_length / total_length.max(constants::EPSILON);
+            *normal = (*normal * angle.cos() + *binormal * angle.sin()).normalize();
+            *binormal = tangent.cross(*normal);
+        }
+        frames
+    }
+
+    fn tube(&self, radius: impl Fn(usize) -> f32, segments: usize) -> Vec<Vector3<f32>> {
+        let vertices = self.get_vertices();
+        let n = vertices.len();
+        if n < 2 || segments < 3 {
+            return vec![];
+        }
+
+        let rings: Vec<Vec<Vector3<f32>>> = self
+            .frames()
+            .iter()
+            .enumerate()
+            .map(|(i, (_, normal, binormal))| {
+                (0..segments)
+                    .map(|k| {
+                        let theta = k as f32 / segments as f32 * 2.0 * consts::PI;
+                        vertices[i] + (normal * theta.cos() + binormal * theta.sin()) * radius(i)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each quad between two rings is split into two triangles, wound counter-clockwise
+        // when seen from outside of the tube
+        let mut triangles = Vec::with_capacity(n * segments * 6);
+        for i in 0..n {
+            let (a, b) = (&rings[i], &rings[(i + 1) % n]);
+            for k in 0..segments {
+                let next = (k + 1) % segments;
+                triangles.extend_from_slice(&[a[k], a[next], b[k], a[next], b[next], b[k]]);
+            }
+        }
+        triangles
+    }
+
+    fn generate_ribbon(&self, width: f32) -> Vec<Vector3<f32>> {
+        let vertices = self.get_vertices();
+        let n = vertices.len();
+        if n < 2 {
+            return vec![];
+        }
+
+        let frames = self.frames();
+        let mut strip = Vec::with_capacity(2 * (n + 1));
+        for i in (0..n).chain(0..1) {
+            let (_, _, binormal) = frames[i];
+            let offset = binormal * (width * 0.5);
+            strip.push(vertices[i] - offset);
+            strip.push(vertices[i] + offset);
+        }
+        strip
+    }
+
+    fn from_parametric(f: impl Fn(f32) -> Vector3<f32>, samples: usize) -> Polyline {
+        let mut polyline = Polyline::new();
+        for i in 0..samples {
+            polyline.push_vertex(&f(i as f32 / samples as f32));
+        }
+        polyline
+    }
+
+    fn from_obj(path: &Path) -> io::Result<Polyline> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let contents = std::fs::read_to_string(path)?;
+
+        let mut vertices = vec![];
+        let mut order = vec![];
+        for (number, line) in contents.lines().enumerate() {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("v") => {
+                    let coordinates = tokens
+                        .take(3)
+                        .map(|token| token.parse::<f32>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| invalid(format!("Line {}: {}", number + 1, e)))?;
+                    if coordinates.len() != 3 {
+                        return Err(invalid(format!(
+                            "Line {}: expected 3 coordinates",
+                            number + 1
+                        )));
+                    }
+                    vertices.push(Vector3::new(coordinates[0], coordinates[1], coordinates[2]));
+                }
+                Some("l") => {
+                    for token in tokens {
+                        // Indices are 1-based, and negative indices count back from the most
+                        // recent vertex (texture coordinate indices, if any, are ignored)
+                        let index = token
+                            .split('/')
+                            .next()
+                            .unwrap_or("")
+                            .parse::<isize>()
+                            .map_err(|e| invalid(format!("Line {}: {}", number + 1, e)))?;
+                        let index = if index < 0 {
+                            vertices.len() as isize + index
+                        } else {
+                            index - 1
+                        };
+                        if index < 0 || index as usize >= vertices.len() {
+                            return Err(invalid(format!(
+                                "Line {}: vertex index out of range",
+                                number + 1
+                            )));
+                        }
+
+                        // Consecutive polyline elements usually share their endpoints
+                        if order.last() != Some(&(index as usize)) {
+                            order.push(index as usize);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if order.is_empty() {
+            order = (0..vertices.len()).collect();
+        }
+        if order.len() > 1
+            && (vertices[order[0]] - vertices[order[order.len() - 1]]).magnitude()
+                < constants::EPSILON
+        {
+            order.pop();
+        }
+        if order.len() < 3 {
+            return Err(invalid(
+                "A closed curve needs at least 3 distinct vertices".to_string(),
+            ));
+        }
+
+        let mut polyline = Polyline::new();
+        for index in order.iter() {
+            polyline.push_vertex(&vertices[*index]);
+        }
+        Ok(polyline)
+    }
+}
+
+/// Returns the index of the ring (i.e. the vertex of the polyline) that the vertex at `index`
+/// of a tube returned by `PolylineExt::tube` lies on, where the tube has `segments` points
+/// around each ring. The index wraps past the last ring, so it should be taken modulo the
+/// number of vertices of the polyline.
+pub fn tube_ring(index: usize, segments: usize) -> usize {
+    // Each quad is made of six vertices, the third, fifth, and sixth of which lie on the
+    // next ring
+    let ring = index / (segments * 6);
+    match index % 6 {
+        2 | 4 | 5 => ring + 1,
+        _ => ring,
+    }
+}
+
+/// Returns the angle through which `polyline` turns at the vertex at `index`, along with the
+/// average length of the two segments adjacent to that vertex. Vertices that coincide with
+/// one of their neighbors don't turn at all.
+fn turning_angle(polyline: &Polyline, index: usize) -> (f32, f32) {
+    let vertices = polyline.get_vertices();
+    let (prev, next) = polyline.get_neighboring_indices_wrapped(index);
+
+    let incoming = vertices[index] - vertices[prev];
+    let outgoing = vertices[next] - vertices[index];
+    if incoming.magnitude() < constants::EPSILON || outgoing.magnitude() < constants::EPSILON {
+        return (0.0, 0.0);
+    }
+    let length = 0.5 * (incoming.magnitude() + outgoing.magnitude());
+
+    let cos_angle = incoming
+        .normalize()
+        .dot(outgoing.normalize())
+        .max(-1.0)
+        .min(1.0);
+    (cos_angle.acos(), length)
+}
+
+/// Returns a unit vector perpendicular to the unit vector `v`.
+fn perpendicular(v: &Vector3<f32>) -> Vector3<f32> {
+    let axis = if v.x.abs() < 0.9 {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    };
+    (axis - v * axis.dot(*v)).normalize()
+}
+
+/// Marks the vertices between `first` and `last` (exclusive) that must be kept so that the
+/// chain `vertices[first..=last]` stays within `tolerance` of its simplification.
+fn douglas_peucker(
+    vertices: &[Vector3<f32>],
+    first: usize,
+    last: usize,
+    tolerance: f32,
+    keep: &mut Vec<bool>,
+) {
+    if last <= first + 1 {
+        return;
+    }
+
+    let (index, distance) = (first + 1..last)
+        .map(|index| {
+            let (point, _) = closest_point(&vertices[first], &vertices[last], &vertices[index]);
+            (index, (vertices[index] - point).magnitude())
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .unwrap();
+
+    if distance > tolerance {
+        keep[index] = true;
+        douglas_peucker(vertices, first, index, tolerance, keep);
+        douglas_peucker(vertices, index, last, tolerance, keep);
+    }
+}
+
+/// Returns the point on the segment from `a` to `b` that is closest to `p`, along with its
+/// parameter `t` (where `0.0` corresponds to `a` and `1.0` corresponds to `b`). The segments
+/// in `graphics_utils` don't expose this query, so it takes the segment's endpoints instead.
+pub fn closest_point(a: &Vector3<f32>, b: &Vector3<f32>, p: &Vector3<f32>) -> (Vector3<f32>, f32) {
+    let ab = b - a;
+    let length_squared = ab.magnitude2();
+
+    // A degenerate segment is a single point
+    if length_squared == 0.0 {
+        return (*a, 0.0);
+    }
+
+    let t = ((p - a).dot(ab) / length_squared).max(0.0).min(1.0);
+    (a + ab * t, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants;
+    use crate::knot::Knot;
+    use crate::utils;
+
+    /// Returns a polyline through the given points (in the xy-plane).
+    fn polyline(points: &[(f32, f32)]) -> Polyline {
+        let mut polyline = Polyline::new();
+        for (x, y) in points.iter() {
+            polyline.push_vertex(&Vector3::new(*x, *y, 0.0));
+        }
+        polyline
+    }
+
+    #[test]
+    fn reverse_twice_is_the_identity() {
+        let points = [(0.0, 0.0), (1.0, 0.0), (1.0, 2.0), (0.0, 3.0)];
+        let original = polyline(&points);
+        let mut reversed = polyline(&points);
+
+        reversed.reverse();
+        assert_eq!(reversed.get_vertices()[0], Vector3::new(0.0, 3.0, 0.0));
+        assert_eq!(reversed.get_vertices()[3], Vector3::new(0.0, 0.0, 0.0));
+
+        reversed.reverse();
+        assert_eq!(reversed.get_vertices(), original.get_vertices());
+    }
+
+    #[test]
+    fn insert_vertex_places_the_vertex() {
+        let mut polyline = polyline(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+        let v = Vector3::new(0.5, -1.0, 0.0);
+
+        polyline.insert_vertex(1, &v);
+        assert_eq!(polyline.get_number_of_vertices(), 4);
+        assert_eq!(polyline.get_vertices()[0], Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(polyline.get_vertices()[1], v);
+        assert_eq!(polyline.get_vertices()[2], Vector3::new(1.0, 0.0, 0.0));
+
+        // Inserting at the end is the same as pushing a vertex
+        polyline.insert_vertex(4, &v);
+        assert_eq!(polyline.get_vertices()[4], v);
+    }
+
+    #[test]
+    fn signed_area_of_a_unit_square() {
+        let mut square = polyline(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        assert!(utils::approx_eq(
+            square.signed_area_xy(),
+            1.0,
+            constants::EPSILON
+        ));
+
+        square.reverse();
+        assert!(utils::approx_eq(
+            square.signed_area_xy(),
+            -1.0,
+            constants::EPSILON
+        ));
+
+        // Only the projection onto the xy-plane matters
+        let mut tilted = Polyline::new();
+        for (x, y, z) in [
+            (0.0, 0.0, 1.0),
+            (1.0, 0.0, 2.0),
+            (1.0, 1.0, 0.0),
+            (0.0, 1.0, -1.0),
+        ]
+        .iter()
+        {
+            tilted.push_vertex(&Vector3::new(*x, *y, *z));
+        }
+        assert!(utils::approx_eq(
+            tilted.signed_area_xy(),
+            1.0,
+            constants::EPSILON
+        ));
+    }
+
+    /// Returns the smallest distance between consecutive vertices of `polyline` (including the
+    /// last and first vertex).
+    fn shortest_edge(polyline: &Polyline) -> f32 {
+        let vertices = polyline.get_vertices();
+        (0..vertices.len())
+            .map(|i| (vertices[(i + 1) % vertices.len()] - vertices[i]).magnitude())
+            .fold(std::f32::MAX, f32::min)
+    }
+
+    #[test]
+    fn parametric_knots() {
+        let trefoil = Polyline::trefoil(120);
+        assert_eq!(trefoil.get_number_of_vertices(), 120);
+        assert!(shortest_edge(&trefoil) > constants::EPSILON);
+        assert_eq!(Knot::new(&trefoil, None).identify(), Some("3_1"));
+
+        // The `(2, 3)` torus knot is also a trefoil
+        let torus_knot = Polyline::torus_knot(2, 3, 90);
+        assert_eq!(torus_knot.get_number_of_vertices(), 90);
+        assert!(shortest_edge(&torus_knot) > constants::EPSILON);
+        assert_eq!(Knot::new(&torus_knot, None).identify(), Some("3_1"));
+
+        // The samples cover `[0, 1)`, so the endpoint isn't repeated
+        let line = Polyline::from_parametric(|s| Vector3::new(s, 0.0, 0.0), 4);
+        assert_eq!(line.get_vertices()[3], Vector3::new(0.75, 0.0, 0.0));
+    }
+
+    #[test]
+    fn from_obj_reads_a_closed_curve() {
+        let directory = std::env::temp_dir().join("knots_tests");
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("closed.obj");
+
+        // The final vertex repeats the first, so it is dropped (the curve is closed anyway)
+        std::fs::write(
+            &path,
+            "# A unit square\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 0\n",
+        )
+        .unwrap();
+        let square = Polyline::from_obj(&path).unwrap();
+        assert_eq!(square.get_number_of_vertices(), 4);
+        assert_eq!(square.get_vertices()[0], Vector3::zero());
+        assert_eq!(square.get_vertices()[3], Vector3::unit_y());
+        assert_eq!(square.signed_area_xy(), 1.0);
+
+        // A polyline element that returns to its start is closed in the same way
+        std::fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\nl 1 2 3 1\n").unwrap();
+        assert_eq!(
+            Polyline::from_obj(&path).unwrap().get_number_of_vertices(),
+            3
+        );
+
+        for contents in ["v 0 0 0\nv 1 0 0\n", "v 0 0\n", "v 0 0 0\nl 1 2 5\n"].iter() {
+            std::fs::write(&path, contents).unwrap();
+            let error = Polyline::from_obj(&path).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn closest_point_on_a_segment() {
+        let a = Vector3::new(1.0, 1.0, 0.0);
+        let b = Vector3::new(5.0, 1.0, 0.0);
+
+        // A point that projects inside of the segment
+        let (point, t) = closest_point(&a, &b, &Vector3::new(2.0, 3.0, 1.0));
+        assert!(utils::approx_eq_vec3(
+            &point,
+            &Vector3::new(2.0, 1.0, 0.0),
+            constants::EPSILON
+        ));
+        assert!(utils::approx_eq(t, 0.25, constants::EPSILON));
+
+        // Points that project before `a` or after `b` are clamped to the endpoints
+        let (point, t) = closest_point(&a, &b, &Vector3::new(-3.0, 0.0, 0.0));
+        assert!(utils::approx_eq_vec3(&point, &a, constants::EPSILON));
+        assert_eq!(t, 0.0);
+
+        let (point, t) = closest_point(&a, &b, &Vector3::new(9.0, 2.0, -1.0));
+        assert!(utils::approx_eq_vec3(&point, &b, constants::EPSILON));
+        assert_eq!(t, 1.0);
+
+        assert_eq!(closest_point(&a, &a, &b), (a, 0.0));
+    }
+
+    /// Returns the distance from `p` to the closest point on the (closed) polyline `polyline`.
+    fn distance_to(polyline: &Polyline, p: &Vector3<f32>) -> f32 {
+        let vertices = polyline.get_vertices();
+        (0..vertices.len())
+            .map(|i| {
+                let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+                (closest_point(&a, &b, p).0 - p).magnitude()
+            })
+            .fold(std::f32::MAX, f32::min)
+    }
+
+    #[test]
+    fn simplify_collapses_collinear_vertices() {
+        // A square with extra vertices along its sides
+        let square = polyline(&[
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 1.5),
+            (3.0, 3.0),
+            (2.0, 3.0),
+            (0.5, 3.0),
+            (0.0, 3.0),
+            (0.0, 1.0),
+        ]);
+        let simplified = square.simplify(0.01);
+        assert_eq!(
+            simplified.get_vertices(),
+            polyline(&[(0.0, 0.0), (3.0, 0.0), (3.0, 3.0), (0.0, 3.0)]).get_vertices()
+        );
+    }
+
+    #[test]
+    fn simplify_stays_within_tolerance() {
+        let tolerance = 0.05;
+        let knot = Polyline::torus_knot(2, 3, 400);
+        let simplified = knot.simplify(tolerance);
+
+        assert!(simplified.get_number_of_vertices() < knot.get_number_of_vertices() / 2);
+        for vertex in knot.get_vertices().iter() {
+            assert!(distance_to(&simplified, vertex) <= tolerance + constants::EPSILON);
+        }
+    }
+
+    /// Samples the unit circle in the xy-plane at `samples` points.
+    fn circle(samples: usize) -> Polyline {
+        Polyline::from_parametric(
+            |s| {
+                let t = s * 2.0 * consts::PI;
+                Vector3::new(t.cos(), t.sin(), 0.0)
+            },
+            samples,
+        )
+    }
+
+    #[test]
+    fn total_curvature_of_a_circle() {
+        let knot = circle(64);
+        assert!(utils::approx_eq(
+            knot.total_curvature(),
+            2.0 * consts::PI,
+            constants::EPSILON
+        ));
+
+        // The curvature of the unit circle is 1 everywhere (up to the discretization)
+        for index in 0..64 {
+            assert!(utils::approx_eq(knot.curvature_at(index), 1.0, 0.01));
+        }
+
+        // Knots turn by more than 4π (Fary-Milnor)
+        assert!(Polyline::trefoil(120).total_curvature() > 4.0 * consts::PI);
+        assert_eq!(
+            polyline(&[(0.0, 0.0), (0.0, 0.0), (1.0, 0.0)]).curvature_at(1),
+            0.0
+        );
+    }
+
+    #[test]
+    fn frames_are_orthonormal() {
+        let trefoil = Polyline::trefoil(120);
+        let frames = trefoil.frames();
+        assert_eq!(frames.len(), 120);
+
+        for (tangent, normal, binormal) in frames.iter() {
+            for v in [tangent, normal, binormal].iter() {
+                assert!(utils::approx_eq(v.magnitude(), 1.0, constants::EPSILON));
+            }
+            assert!(tangent.dot(*normal).abs() < constants::EPSILON);
+            assert!(tangent.dot(*binormal).abs() < constants::EPSILON);
+            assert!(normal.dot(*binormal).abs() < constants::EPSILON);
+            assert!(utils::approx_eq_vec3(
+                &tangent.cross(*normal),
+                binormal,
+                constants::EPSILON
+            ));
+        }
+
+        // The first normal of a circle points towards its center
+        let (_, normal, _) = circle(64).frames()[0];
+        assert!(utils::approx_eq_vec3(
+            &normal,
+            &Vector3::new(-1.0, 0.0, 0.0),
+            constants::EPSILON
+        ));
+    }
+
+    #[test]
+    fn frames_close_up_around_the_seam() {
+        let trefoil = Polyline::trefoil(120);
+        let frames = trefoil.frames();
+
+        // Transporting the last normal onto the first tangent lands (close to) the first normal
+        let (tangent, normal, _) = frames[0];
+        let (_, last_normal, _) = frames[119];
+        let closing = (last_normal - tangent * last_normal.dot(tangent)).normalize();
+        assert!(closing.dot(normal) > 0.99, "{}", closing.dot(normal));
+
+        // ...and neighboring frames only turn a little
+        for i in 0..120 {
+            let (_, a, _) = frames[i];
+            let (_, b, _) = frames[(i + 1) % 120];
+            assert!(a.dot(b) > 0.9, "{} {}", i, a.dot(b));
+        }
+    }
+
+    #[test]
+    fn tube_faces_point_outwards() {
+        let segments = 8;
+        for knot in [circle(60), Polyline::trefoil(60)].iter() {
+            let tube = knot.tube(|_| 0.2, segments);
+            assert_eq!(tube.len(), 60 * segments * 6);
+
+            let vertices = knot.get_vertices();
+            for (index, triangle) in tube.chunks(3).enumerate() {
+                let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+                let ring = tube_ring(index * 3, segments) % 60;
+                let centroid = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+                assert!(
+                    normal.dot(centroid - vertices[ring]) > 0.0,
+                    "{} {}",
+                    index,
+                    normal.dot(centroid - vertices[ring])
+                );
+            }
+
+            // Every vertex lies on the ring around its vertex of the polyline
+            for (index, vertex) in tube.iter().enumerate() {
+                let center = vertices[tube_ring(index, segments) % 60];
+                assert!(utils::approx_eq(
+                    (vertex - center).magnitude(),
+                    0.2,
+                    constants::EPSILON
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn ribbons_lie_in_the_plane_of_a_planar_curve() {
+        let knot = circle(32);
+        let ribbon = knot.generate_ribbon(0.5);
+        assert_eq!(ribbon.len(), 2 * (32 + 1));
+
+        // For a curve in the xy-plane, the binormal is the z-axis, so both sides of the ribbon
+        // sit directly above and below each vertex
+        let vertices = knot.get_vertices();
+        for (index, point) in ribbon.iter().enumerate() {
+            let vertex = vertices[(index / 2) % 32];
+            assert!(utils::approx_eq(point.x, vertex.x, constants::EPSILON));
+            assert!(utils::approx_eq(point.y, vertex.y, constants::EPSILON));
+            assert!(utils::approx_eq(point.z.abs(), 0.25, constants::EPSILON));
+        }
+
+        // The strip closes up on itself
+        assert!(utils::approx_eq_vec3(
+            &ribbon[0],
+            &ribbon[64],
+            constants::EPSILON
+        ));
+        assert!(utils::approx_eq_vec3(
+            &ribbon[1],
+            &ribbon[65],
+            constants::EPSILON
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::diagram::CromwellMove::{Commutation, Stabilization, Translation};
+use crate::gauss_code;
 use crate::knot::Knot;
 use cgmath::Vector3;
 use graphics_utils::polyline::Polyline;
@@ -11,7 +12,7 @@ use std::io;
 use std::path::Path;
 
 /// An enum representing a direction (see `CromwellMove::Translation`).
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Direction {
     Up,
     Down,
@@ -19,15 +20,35 @@ pub enum Direction {
     Right,
 }
 
+impl Distribution<Direction> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Direction {
+        match rng.gen_range(0, 4) {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+}
+
 /// An enum representing an axial direction (either rows or columns).
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Axis {
     Row,
     Column,
 }
 
+impl Distribution<Axis> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Axis {
+        match rng.gen_range(0, 2) {
+            0 => Axis::Row,
+            _ => Axis::Column,
+        }
+    }
+}
+
 /// An enum representing a cardinal direction (as on a compass).
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Cardinality {
     NW,
     SW,
@@ -35,16 +56,29 @@ pub enum Cardinality {
     SE,
 }
 
+impl Distribution<Cardinality> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Cardinality {
+        match rng.gen_range(0, 4) {
+            0 => Cardinality::NW,
+            1 => Cardinality::SW,
+            2 => Cardinality::NE,
+            _ => Cardinality::SE,
+        }
+    }
+}
+
 /// An enum representing the Cromwell moves, which are essentially Reidemeister
 /// moves for grid diagrams. A sequence of Cromwell moves does not change the
 /// knot invariant but rather, produces a new projection of the same knot.
 ///
 /// Reference: `https://www.math.ucdavis.edu/~slwitte/research/BlackwellTapiaPoster.pdf`
+#[derive(Clone, Copy, Debug)]
 pub enum CromwellMove {
     // A move that cyclically translates a row or column in one of four directions: up, down, left, or right
     Translation(Direction),
 
-    // A move that exchanges to adjacent, non-interleaved rows or columns
+    // A move that exchanges two adjacent, non-interleaved rows or columns (the last row or column
+    // is considered to be adjacent to the first)
     Commutation {
         axis: Axis,
         start_index: usize,
@@ -56,8 +90,39 @@ pub enum CromwellMove {
         i: usize,
         j: usize,
     },
-    // A move that replaces a 2x2 sub-grid with an `x` (the opposite of an x-stabilization): currently not supported
-    //Destabilization,
+
+    // A move that replaces a 2x2 sub-grid containing three marks with a single mark (the opposite of
+    // a stabilization): `i` and `j` are the indices of the top-left cell of the sub-grid and the
+    // cardinal direction designates the corner of the sub-grid that contains the "blank" cell
+    Destabilization {
+        cardinality: Cardinality,
+        i: usize,
+        j: usize,
+    },
+}
+
+/// Parameters that control how a grid diagram is embedded in 3-space by
+/// `Diagram::generate_knot_with`.
+#[derive(Clone, Copy, Debug)]
+pub struct GenerateOptions {
+    // The world-space width (and height) of each cell of the grid
+    pub scale: f32,
+
+    // How far each vertical strand is lifted along the z-axis where it passes over a horizontal strand
+    pub lift_amount: f32,
+
+    // The maximum length of each segment of the knot's polyline after it is subdivided
+    pub refine_length: f32,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        GenerateOptions {
+            scale: 1.0,
+            lift_amount: 0.1,
+            refine_length: 0.5,
+        }
+    }
 }
 
 trait KnotGenerator {
@@ -66,60 +131,208 @@ trait KnotGenerator {
 
 /// A struct representing a grid diagram corresponding to a particular knot invariant (or
 /// the unknot).
+#[derive(Clone)]
 pub struct Diagram {
-    // The number of rows and columns in the grid diagram (we assume all diagrams are square)
-    resolution: usize,
+    // The number of rows in the grid diagram
+    rows: usize,
+
+    // The number of columns in the grid diagram (this should equal `rows`, except in partial or
+    // intermediate grids)
+    cols: usize,
 
     // The grid data (i.e. a 2D array of x's, o's, and blank cells)
     data: Vec<Vec<char>>,
+
+    // Snapshots of the dimensions and grid data prior to each successful Cromwell move (most recent last)
+    history: Vec<(usize, usize, Vec<Vec<char>>)>,
 }
 
 impl Diagram {
     /// Generates a grid diagram from a .csv file, where each entry is either ` `, `x`, or `o`.
     /// Internally, a grid diagram maintains a 2D array of `char`s, where the first axis is the rows
     /// and the second axis is the columns.
-    pub fn from_path(path: &Path) -> Result<Diagram, &'static str> {
-        if let Some(".csv") = path.extension().and_then(OsStr::to_str) {
-            return Err("Only .csv grid files are supported at the moment");
+    pub fn from_path(path: &Path) -> Result<Diagram, String> {
+        if path.extension().and_then(OsStr::to_str) != Some("csv") {
+            return Err(format!(
+                "Only .csv grid files are supported at the moment (got `{}`)",
+                path.display()
+            ));
         }
 
-        let mut resolution = 0;
-        let mut data: Vec<Vec<char>> = vec![];
-        let mut reader = csv::ReaderBuilder::new()
+        // We check for ragged rows ourselves (below), so that we can report which row is at fault
+        let mut reader = match csv::ReaderBuilder::new()
             .has_headers(false)
+            .flexible(true)
             .from_path(path)
-            .unwrap();
-        let mut number_of_rows = 0;
+        {
+            Ok(reader) => reader,
+            Err(e) => {
+                return Err(format!(
+                    "Failed to open grid file `{}`: {}",
+                    path.display(),
+                    e
+                ))
+            }
+        };
 
-        for result in reader.records() {
-            let record = result.unwrap();
-            resolution = record.len();
-            number_of_rows += 1;
+        let mut data: Vec<Vec<char>> = vec![];
+        for (i, result) in reader.records().enumerate() {
+            let record = match result {
+                Ok(record) => record,
+                Err(e) => return Err(format!("Failed to read row {} of grid file: {}", i, e)),
+            };
+
+            // Every row should have as many columns as the first
+            if let Some(first) = data.first() {
+                if record.len() != first.len() {
+                    return Err(format!(
+                        "Row {} of grid file has {} columns, but row 0 has {}",
+                        i,
+                        record.len(),
+                        first.len()
+                    ));
+                }
+            }
+
+            // Each field should be a single character (an empty field is treated as blank)
+            let mut row = vec![];
+            for (j, field) in record.iter().enumerate() {
+                let mut chars = field.chars();
+                match (chars.next(), chars.next()) {
+                    (None, _) => row.push(' '),
+                    (Some(entry), None) => row.push(entry),
+                    _ => {
+                        return Err(format!(
+                            "Row {}, column {} of grid file contains `{}` (expected a single character)",
+                            i, j, field
+                        ));
+                    }
+                }
+            }
 
             // Push this row of data
-            data.push(record.as_slice().chars().collect());
+            data.push(row);
         }
 
-        // Verify that the grid is square
-        if resolution != number_of_rows {
-            return Err("Provided grid file is not square: the number of rows should equal the number of columns");
-        }
+        println!(
+            "Building a {}x{} grid diagram",
+            data.len(),
+            data.first().map_or(0, |row| row.len())
+        );
+        Diagram::from_data(data)
+    }
 
-        println!("Building a {}x{} grid diagram", resolution, resolution);
-        let diagram = Diagram { resolution, data };
+    /// Generates a grid diagram from a 2D array of `char`s, where the first axis is the rows
+    /// and the second axis is the columns. Each entry should be either ` `, `x`, or `o`.
+    /// Every row should have the same length, but the grid itself need not be square (see
+    /// `Diagram::is_square`).
+    pub fn from_data(data: Vec<Vec<char>>) -> Result<Diagram, String> {
+        let rows = data.len();
+        let cols = data.first().map_or(0, |row| row.len());
+
+        // Verify that the grid is rectangular
+        if let Some(i) = data.iter().position(|row| row.len() != cols) {
+            return Err(format!(
+                "Provided grid is ragged: row {} has {} columns, but row 0 has {}",
+                i,
+                data[i].len(),
+                cols
+            ));
+        }
 
-        return match diagram.validate() {
-            Ok(_) => Ok(diagram),
-            Err(e) => Err(e),
+        let diagram = Diagram {
+            rows,
+            cols,
+            data,
+            history: vec![],
         };
+
+        diagram.validate()?;
+        Ok(diagram)
+    }
+
+    /// Generates a grid diagram from a signed Gauss code, such as `O1+U2+O3+U1+O2+U3+` (the
+    /// trefoil). Each crossing is visited twice while traversing the knot: once along the
+    /// over-strand (`O`) and once along the under-strand (`U`), followed by the sign of the
+    /// crossing (`+` or `-`). An empty code corresponds to the unknot.
+    ///
+    /// The projection of the resulting grid diagram has the same Gauss code, up to the
+    /// removal of nugatory crossings (see `gauss_code::realize`). Codes that cannot be drawn
+    /// in the plane (for example, `O1+O2+U1+U2+`) result in an error.
+    pub fn from_gauss_code(code: &str) -> Result<Diagram, &'static str> {
+        // Parse the code into a list of `(label, is_over, sign)` tuples
+        let mut entries = vec![];
+        let mut chars = code.chars().filter(|c| !c.is_whitespace()).peekable();
+
+        while let Some(strand) = chars.next() {
+            let is_over = match strand {
+                'O' | 'o' => true,
+                'U' | 'u' => false,
+                _ => return Err("Each entry of a Gauss code should start with `O` or `U`"),
+            };
+
+            let mut label = String::new();
+            while let Some(digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                label.push(*digit);
+                chars.next();
+            }
+            let label: usize = match label.parse() {
+                Ok(label) => label,
+                Err(_) => return Err("Each entry of a Gauss code should contain a crossing label"),
+            };
+
+            let sign = match chars.next() {
+                Some('+') => 1,
+                Some('-') => -1,
+                _ => return Err("Each entry of a Gauss code should end with a sign (`+` or `-`)"),
+            };
+
+            entries.push((label, is_over, sign));
+        }
+
+        // Each crossing must be visited exactly twice (once over, once under) with a consistent sign
+        for (label, is_over, sign) in entries.iter() {
+            let visits: Vec<&(usize, bool, i32)> =
+                entries.iter().filter(|entry| entry.0 == *label).collect();
+
+            if visits.len() != 2 {
+                return Err("Invalid Gauss code: each crossing should appear exactly twice");
+            }
+            if visits.iter().filter(|entry| entry.1 == *is_over).count() != 1 {
+                return Err(
+                    "Invalid Gauss code: each crossing should be passed over once and under once",
+                );
+            }
+            if visits.iter().any(|entry| entry.2 != *sign) {
+                return Err(
+                    "Invalid Gauss code: both visits to a crossing should have the same sign",
+                );
+            }
+        }
+
+        let data = gauss_code::realize(&entries)?;
+        Diagram::from_data(data).map_err(|_| "Failed to build a grid diagram from the Gauss code")
     }
 
     /// Applies a particular Cromwell move to the grid diagram.
     ///
     /// Reference: `https://arxiv.org/pdf/1903.05893.pdf`
     pub fn apply_move(&mut self, cromwell: CromwellMove) -> Result<&mut Self, &'static str> {
+        self.check_move(&cromwell)?;
+
         println!("Grid diagram before Cromwell move:");
         println!("{:?}", self);
+        self.perform_move(cromwell);
+        println!("Grid diagram after Cromwell move:");
+        println!("{:?}", self);
+        Ok(self)
+    }
+
+    /// Applies the Cromwell move `cromwell`, which must have already been checked with
+    /// `check_move`, and records the previous grid in the history (without printing anything).
+    fn perform_move(&mut self, cromwell: CromwellMove) {
+        let snapshot = (self.rows, self.cols, self.data.clone());
+
         match cromwell {
             CromwellMove::Translation(direction) => match direction {
                 Direction::Up => {
@@ -146,37 +359,15 @@ impl Diagram {
                 }
             },
             CromwellMove::Commutation { axis, start_index } => {
-                // The last row (or column) doesn't have any adjacent row (or column) to swap with
-                if start_index == self.resolution - 1 {
-                    return Err("Cannot exchange row or column at `start_index` with non-existing adjacent row or column");
-                }
-
-                // Grab the two rows (or columns) that will be exchanged
-                let (row_or_column_a, row_or_column_b) = match axis {
-                    Axis::Row => (self.get_row(start_index + 0), self.get_row(start_index + 1)),
-                    _ => (
-                        self.get_column(start_index + 0),
-                        self.get_column(start_index + 1),
-                    ),
-                };
-
-                // Commutation is only valid if the two rows (or columns) are not interleaved
-                if !self.are_interleaved(&row_or_column_a, &row_or_column_b) {
-                    match axis {
-                        Axis::Row => self.exchange_rows(start_index + 0, start_index + 1),
-                        _ => self.exchange_columns(start_index + 0, start_index + 1),
-                    }
-                } else {
-                    return Err(
-                        "The specified rows (or columns) are interleaved and cannot be exchanged",
-                    );
+                // The grid diagram lives on a torus, so the last row (or column) is adjacent to
+                // the first row (or column): the grid is known to be square at this point
+                let end_index = (start_index + 1) % self.rows;
+                match axis {
+                    Axis::Row => self.exchange_rows(start_index, end_index),
+                    _ => self.exchange_columns(start_index, end_index),
                 }
             }
             CromwellMove::Stabilization { cardinality, i, j } => {
-                if self.data[i][j] != 'x' {
-                    return Err("There is no `x` at the specified grid position: stabilization cannot be performed");
-                }
-
                 // The cardinal directions below designate the corner of the new 2x2 sub-grid
                 // that contains a "blank" cell (i.e. where the original `x` resided, for an
                 // x-stabilization)
@@ -194,13 +385,14 @@ impl Diagram {
                         }
                     }
                 }
-                self.resolution += 1;
+                self.rows += 1;
+                self.cols += 1;
 
                 match cardinality {
                     Cardinality::NW => {
                         self.data[i][j + 0] = ' ';
                         self.data[i][j + 1] = 'x';
-                        let mut extra_row = vec![' '; self.resolution];
+                        let mut extra_row = vec![' '; self.cols];
                         extra_row[j + 0] = 'x';
                         extra_row[j + 1] = 'o';
                         self.data.insert(i + 1, extra_row);
@@ -208,7 +400,7 @@ impl Diagram {
                     Cardinality::SW => {
                         self.data[i][j + 0] = ' ';
                         self.data[i][j + 1] = 'x';
-                        let mut extra_row = vec![' '; self.resolution];
+                        let mut extra_row = vec![' '; self.cols];
                         extra_row[j + 0] = 'x';
                         extra_row[j + 1] = 'o';
                         self.data.insert(i + 0, extra_row);
@@ -216,7 +408,7 @@ impl Diagram {
                     Cardinality::NE => {
                         self.data[i][j + 0] = 'x'; // Technically, this is unnecessary
                         self.data[i][j + 1] = ' ';
-                        let mut extra_row = vec![' '; self.resolution];
+                        let mut extra_row = vec![' '; self.cols];
                         extra_row[j + 0] = 'o';
                         extra_row[j + 1] = 'x';
                         self.data.insert(i + 1, extra_row);
@@ -224,45 +416,409 @@ impl Diagram {
                     Cardinality::SE => {
                         self.data[i][j + 0] = 'x'; // Technically, this is unnecessary
                         self.data[i][j + 1] = ' ';
-                        let mut extra_row = vec![' '; self.resolution];
+                        let mut extra_row = vec![' '; self.cols];
                         extra_row[j + 0] = 'o';
                         extra_row[j + 1] = 'x';
                         self.data.insert(i + 0, extra_row);
                     }
                 }
             }
+            CromwellMove::Destabilization { cardinality, i, j } => {
+                // The row and column of the blank cell survive, while the other row and column
+                // of the sub-grid (each of which contains two marks) are removed
+                let (blank_row, other_row) = match cardinality {
+                    Cardinality::NW | Cardinality::NE => (i + 0, i + 1),
+                    _ => (i + 1, i + 0),
+                };
+                let other_col = match cardinality {
+                    Cardinality::NW | Cardinality::SW => j + 1,
+                    _ => j + 0,
+                };
+
+                // The two marks adjacent to the blank cell are always of the same type: this
+                // is the mark that replaces the sub-grid
+                let mark = self.data[blank_row][other_col];
+
+                self.data.remove(other_row);
+                for row in self.data.iter_mut() {
+                    row.remove(other_col);
+                }
+                self.rows -= 1;
+                self.cols -= 1;
+
+                // After the removal above, the blank cell is always the top-left cell of what
+                // remains of the sub-grid
+                self.data[i][j] = mark;
+            }
+        }
+        self.history.push(snapshot);
+    }
+
+    /// Returns `true` if the Cromwell move `cromwell` can be applied to this grid diagram, i.e.
+    /// `apply_move` would succeed. The grid diagram itself is left untouched.
+    pub fn can_apply(&self, cromwell: &CromwellMove) -> bool {
+        self.check_move(cromwell).is_ok()
+    }
+
+    /// Checks whether the Cromwell move `cromwell` can be applied to this grid diagram,
+    /// returning an error that describes why not otherwise.
+    fn check_move(&self, cromwell: &CromwellMove) -> Result<(), &'static str> {
+        match *cromwell {
+            CromwellMove::Translation(_) => {
+                if self.data.is_empty() {
+                    return Err("Cannot translate an empty grid diagram");
+                }
+            }
+            CromwellMove::Commutation { axis, start_index } => {
+                // Rows (or columns) of a partial grid may be missing marks
+                if !self.is_square() {
+                    return Err("Rows and columns can only be exchanged in square grid diagrams");
+                }
+
+                let count = match axis {
+                    Axis::Row => self.rows,
+                    _ => self.cols,
+                };
+                if start_index >= count {
+                    return Err(
+                        "Cannot exchange row or column at `start_index`: index is out of bounds",
+                    );
+                }
+                let end_index = (start_index + 1) % count;
+
+                // Grab the two rows (or columns) that will be exchanged
+                let (row_or_column_a, row_or_column_b) = match axis {
+                    Axis::Row => (self.get_row(start_index), self.get_row(end_index)),
+                    _ => (self.get_column(start_index), self.get_column(end_index)),
+                };
+
+                // Commutation is only valid if the two rows (or columns) are not interleaved
+                if self.are_interleaved(&row_or_column_a, &row_or_column_b)? {
+                    return Err(
+                        "The specified rows (or columns) are interleaved and cannot be exchanged",
+                    );
+                }
+            }
+            CromwellMove::Stabilization { i, j, .. } => {
+                if self.get(i, j) != Some('x') {
+                    return Err("There is no `x` at the specified grid position: stabilization cannot be performed");
+                }
+            }
+            CromwellMove::Destabilization { cardinality, i, j } => {
+                if !self.can_destabilize(cardinality, i, j) {
+                    return Err("There is no 2x2 sub-grid with a blank cell in the specified corner at the specified grid position: destabilization cannot be performed");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reverts the most recently applied Cromwell move.
+    pub fn undo(&mut self) -> Result<&mut Self, &'static str> {
+        match self.history.pop() {
+            Some((rows, cols, data)) => {
+                self.rows = rows;
+                self.cols = cols;
+                self.data = data;
+                Ok(self)
+            }
+            None => Err("There are no Cromwell moves to undo"),
+        }
+    }
+
+    /// Applies a sequence of Cromwell moves to the grid diagram, in order. If any of the
+    /// moves fails, the remaining moves are skipped and the returned error names the index
+    /// of the offending move (the moves before it will have already been applied).
+    pub fn apply_sequence(&mut self, moves: Vec<CromwellMove>) -> Result<&mut Self, String> {
+        for (index, cromwell) in moves.into_iter().enumerate() {
+            if let Err(e) = self.apply_move(cromwell) {
+                return Err(format!("Cromwell move at index {} failed: {}", index, e));
+            }
         }
-        println!("Grid diagram after Cromwell move:");
-        println!("{:?}", self);
         Ok(self)
     }
 
+    /// Returns every Cromwell move that can currently be applied to this grid diagram: all
+    /// four translations, each commutation of adjacent rows (or columns) that are not
+    /// interleaved, every stabilization of every `x`, and every possible destabilization.
+    fn legal_moves(&self) -> Vec<CromwellMove> {
+        let mut moves = vec![
+            CromwellMove::Translation(Direction::Up),
+            CromwellMove::Translation(Direction::Down),
+            CromwellMove::Translation(Direction::Left),
+            CromwellMove::Translation(Direction::Right),
+        ];
+
+        if self.is_square() && self.rows > 1 {
+            for start_index in 0..self.rows {
+                let end_index = (start_index + 1) % self.rows;
+                let pairs = [
+                    (
+                        Axis::Row,
+                        self.get_row(start_index),
+                        self.get_row(end_index),
+                    ),
+                    (
+                        Axis::Column,
+                        self.get_column(start_index),
+                        self.get_column(end_index),
+                    ),
+                ];
+                for (axis, a, b) in pairs.iter() {
+                    // Malformed rows (or columns) are never legal to exchange
+                    if let Ok(false) = self.are_interleaved(a, b) {
+                        moves.push(CromwellMove::Commutation {
+                            axis: *axis,
+                            start_index,
+                        });
+                    }
+                }
+            }
+        }
+
+        let cardinalities = [
+            Cardinality::NW,
+            Cardinality::NE,
+            Cardinality::SW,
+            Cardinality::SE,
+        ];
+        for (i, j) in self.x_positions() {
+            for cardinality in cardinalities.iter() {
+                moves.push(CromwellMove::Stabilization {
+                    cardinality: *cardinality,
+                    i,
+                    j,
+                });
+            }
+        }
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                for cardinality in cardinalities.iter() {
+                    if self.can_destabilize(*cardinality, i, j) {
+                        moves.push(CromwellMove::Destabilization {
+                            cardinality: *cardinality,
+                            i,
+                            j,
+                        });
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    /// Picks a Cromwell move uniformly at random from all of the moves that can currently be
+    /// applied to this grid diagram, applies it, and returns it. Repeatedly calling this
+    /// performs a random walk through the projections of the underlying knot (which is
+    /// deterministic for a seeded `rng`). Note that stabilizations usually make up most of
+    /// the legal moves, so the grid tends to grow over the course of a walk. Each move is
+    /// recorded in the history (so it can be undone), but unlike `apply_move`, nothing is
+    /// printed.
+    pub fn random_move(&mut self, rng: &mut impl Rng) -> Result<CromwellMove, &'static str> {
+        let moves = self.legal_moves();
+        if moves.is_empty() {
+            return Err("There are no Cromwell moves that can be applied to this grid diagram");
+        }
+
+        let cromwell = moves[rng.gen_range(0, moves.len())];
+        self.check_move(&cromwell)?;
+        self.perform_move(cromwell);
+        Ok(cromwell)
+    }
+
+    /// Returns a canonical representative of this grid diagram up to (cyclic) translation:
+    /// out of every diagram that can be reached from this one with translations alone, the
+    /// one whose grid is lexicographically smallest. Two diagrams that differ only by a
+    /// sequence of translations have equal canonical forms. The history of the returned
+    /// diagram is empty.
+    pub fn canonicalize(&self) -> Diagram {
+        let mut canonical = self.data.clone();
+
+        for row_offset in 0..self.rows {
+            for col_offset in 0..self.cols {
+                let translated: Vec<Vec<char>> = (0..self.rows)
+                    .map(|i| {
+                        let row = &self.data[(i + row_offset) % self.rows];
+                        (0..self.cols)
+                            .map(|j| row[(j + col_offset) % self.cols])
+                            .collect()
+                    })
+                    .collect();
+
+                if translated < canonical {
+                    canonical = translated;
+                }
+            }
+        }
+
+        Diagram {
+            rows: self.rows,
+            cols: self.cols,
+            data: canonical,
+            history: vec![],
+        }
+    }
+
+    /// Returns the arc index (or grid number) of this grid diagram, which is simply its
+    /// number of columns.
+    pub fn arc_index(&self) -> usize {
+        self.cols
+    }
+
+    /// Repeatedly applies destabilizations to reduce the grid number of this diagram as far
+    /// as possible, returning the final grid number. Whenever no destabilization is possible,
+    /// a single translation or commutation that uncovers one is applied first. Note that this
+    /// is a greedy search, so the result is not guaranteed to be the minimal grid number of
+    /// the underlying knot. Each move is recorded in the history (so it can be undone), but
+    /// unlike `apply_move`, nothing is printed.
+    pub fn minimize(&mut self) -> usize {
+        loop {
+            if let Some(destabilization) = self.find_destabilization() {
+                self.perform_move(destabilization);
+                continue;
+            }
+
+            // Look one move ahead for a translation or commutation that allows the grid
+            // to be destabilized
+            let mut candidates = vec![
+                CromwellMove::Translation(Direction::Up),
+                CromwellMove::Translation(Direction::Left),
+            ];
+            for (axis, count) in [(Axis::Row, self.rows), (Axis::Column, self.cols)].iter() {
+                for start_index in 0..*count {
+                    candidates.push(CromwellMove::Commutation {
+                        axis: *axis,
+                        start_index,
+                    });
+                }
+            }
+
+            // Trial moves are applied quietly to a copy of the grid
+            let uncovering = candidates.into_iter().find(|candidate| {
+                if !self.can_apply(candidate) {
+                    return false;
+                }
+                let mut trial = self.clone();
+                trial.perform_move(*candidate);
+                trial.find_destabilization().is_some()
+            });
+
+            match uncovering {
+                Some(cromwell) => self.perform_move(cromwell),
+                None => break,
+            }
+        }
+        self.arc_index()
+    }
+
+    /// Returns `true` if the 2x2 sub-grid whose top-left cell is at `<i, j>` can be
+    /// destabilized, i.e. it contains three marks and its blank cell is in the corner
+    /// designated by `cardinality`.
+    fn can_destabilize(&self, cardinality: Cardinality, i: usize, j: usize) -> bool {
+        // A 2x2 grid cannot be reduced any further
+        if self.rows <= 2 || self.cols <= 2 || i + 1 >= self.rows || j + 1 >= self.cols {
+            return false;
+        }
+
+        let blank = match cardinality {
+            Cardinality::NW => (i + 0, j + 0),
+            Cardinality::NE => (i + 0, j + 1),
+            Cardinality::SW => (i + 1, j + 0),
+            Cardinality::SE => (i + 1, j + 1),
+        };
+
+        for row in i..i + 2 {
+            for col in j..j + 2 {
+                let is_blank = self.data[row][col] == ' ';
+                if is_blank != ((row, col) == blank) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the first destabilization that can be applied to this grid diagram, if any.
+    pub fn find_destabilization(&self) -> Option<CromwellMove> {
+        let cardinalities = [
+            Cardinality::NW,
+            Cardinality::NE,
+            Cardinality::SW,
+            Cardinality::SE,
+        ];
+
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                for cardinality in cardinalities.iter() {
+                    if self.can_destabilize(*cardinality, i, j) {
+                        return Some(CromwellMove::Destabilization {
+                            cardinality: *cardinality,
+                            i,
+                            j,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
     /// Generates a random, valid grid diagram that may or may not be the unknot.
     pub fn random() {
         unimplemented!()
     }
 
-    /// Validates the grid diagram, ensuring that there is only one `x` and one `o`
-    /// per column and row.
-    fn validate(&self) -> Result<(), &'static str> {
-        for index in 0..self.resolution {
-            let current_row = self.get_row(index);
-            let current_col = self.get_column(index);
+    /// Validates the grid diagram, ensuring that every entry is either ` `, `x`, or `o` and
+    /// that there is only one `x` and one `o` per column and row. Rows and columns of a
+    /// rectangular (partial) grid are only required to have at most one of each. If the grid
+    /// is invalid, the returned error describes the first offending entry, row, or column.
+    fn validate(&self) -> Result<(), String> {
+        for ((i, j), entry) in self.marks() {
+            if entry != 'x' && entry != 'o' {
+                return Err(format!(
+                    "Invalid grid diagram: row {}, column {} contains the character '{}' (expected ' ', 'x', or 'o')",
+                    i, j, entry
+                ));
+            }
+        }
 
-            if current_row.iter().collect::<String>().matches('x').count() != 1
-                || current_row.iter().collect::<String>().matches('o').count() != 1
-                || current_col.iter().collect::<String>().matches('x').count() != 1
-                || current_col.iter().collect::<String>().matches('o').count() != 1
-            {
-                return Err("Invalid grid diagram: ensure that each column / row contains exactly one `x` and one `o`");
+        let rows = (0..self.rows).map(|i| ("row", i, self.get_row(i)));
+        let cols = (0..self.cols).map(|j| ("column", j, self.get_column(j)));
+
+        for (name, index, entries) in rows.chain(cols) {
+            for mark in ['x', 'o'].iter() {
+                let count = entries.iter().filter(|entry| *entry == mark).count();
+                if count > 1 || (count == 0 && self.is_square()) {
+                    return Err(format!(
+                        "Invalid grid diagram: {} {} contains {} '{}' marks (expected exactly 1)",
+                        name, index, count, mark
+                    ));
+                }
             }
         }
         Ok(())
     }
 
     /// Returns the resolution of this grid diagram (i.e. the number of rows or number of columns).
+    /// For rectangular grids, this is the number of rows.
     pub fn get_resolution(&self) -> usize {
-        self.resolution
+        self.rows
+    }
+
+    /// Returns the number of rows in this grid diagram.
+    pub fn get_number_of_rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns in this grid diagram.
+    pub fn get_number_of_columns(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns `true` if this grid diagram has as many rows as columns. Only square grids
+    /// correspond to knots: rectangular grids are partial (or intermediate) diagrams.
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
     }
 
     /// Returns an immutable reference to this grid diagram's internal data store.
@@ -270,6 +826,84 @@ impl Diagram {
         &self.data
     }
 
+    /// Returns the entry in row `i` and column `j` of this grid diagram, or `None` if the
+    /// indices are out of range.
+    pub fn get(&self, i: usize, j: usize) -> Option<char> {
+        self.data.get(i)?.get(j).cloned()
+    }
+
+    /// Sets the entry in row `i` and column `j` of this grid diagram to `c`, which must be
+    /// one of ` `, `x`, or `o`. Since moving a single mark always requires (at least) two
+    /// edits, the grid is allowed to pass through invalid states: it is re-validated after
+    /// the entry is set, and a warning is printed if it is no longer a valid grid diagram.
+    pub fn set(&mut self, i: usize, j: usize, c: char) -> Result<(), &'static str> {
+        if i >= self.rows || j >= self.cols {
+            return Err("The specified grid position is out of range");
+        }
+        if c != ' ' && c != 'x' && c != 'o' {
+            return Err("Grid diagram entries must be one of ' ', 'x', or 'o'");
+        }
+        self.data[i][j] = c;
+
+        if let Err(e) = self.validate() {
+            println!("Warning: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Reflects this grid diagram across its main diagonal, exchanging its rows and columns.
+    /// The reflection swaps which strand passes over at each crossing (vertical strands are
+    /// always on top), which cancels out the reflection itself: the result is the original
+    /// knot rotated half a turn about the diagonal, with its orientation reversed (so the
+    /// writhe is unchanged). Transposing twice restores the original grid.
+    pub fn transpose(&mut self) {
+        self.data = (0..self.cols).map(|j| self.get_column(j)).collect();
+        std::mem::swap(&mut self.rows, &mut self.cols);
+    }
+
+    /// Reverses the order of the rows (if `axis` is `Axis::Row`) or columns (if `axis` is
+    /// `Axis::Column`) of this grid diagram, i.e. reflects it vertically or horizontally. The
+    /// result is a diagram of the mirror image of the original knot, so its writhe is negated
+    /// (and the Thurston-Bennequin numbers of the two grids sum to minus the grid number).
+    /// Mirroring twice along the same axis restores the original grid.
+    pub fn mirror(&mut self, axis: Axis) {
+        match axis {
+            Axis::Row => self.data.reverse(),
+            Axis::Column => {
+                for row in self.data.iter_mut() {
+                    row.reverse();
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over every non-blank entry of this grid diagram (in row-major
+    /// order), along with its grid indices `<i, j>`.
+    pub fn marks(&self) -> impl Iterator<Item = ((usize, usize), char)> + '_ {
+        self.data.iter().enumerate().flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, entry)| **entry != ' ')
+                .map(move |(j, entry)| ((i, j), *entry))
+        })
+    }
+
+    /// Returns the grid indices `<i, j>` of every `x` in this grid diagram (in row-major order).
+    pub fn x_positions(&self) -> Vec<(usize, usize)> {
+        self.marks()
+            .filter(|(_, mark)| *mark == 'x')
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Returns the grid indices `<i, j>` of every `o` in this grid diagram (in row-major order).
+    pub fn o_positions(&self) -> Vec<(usize, usize)> {
+        self.marks()
+            .filter(|(_, mark)| *mark == 'o')
+            .map(|(position, _)| position)
+            .collect()
+    }
+
     /// Sets the values of the `i`th row to `row`.
     fn set_row(&mut self, i: usize, row: &Vec<char>) {
         self.data[i] = row.clone();
@@ -306,8 +940,13 @@ impl Diagram {
     }
 
     /// Checks whether two rows (or columns) are interleaved, i.e. their projections
-    /// onto the x-axis (or y-axis, respectively) overlap.
-    fn are_interleaved(&self, row_or_column_a: &Vec<char>, row_or_column_b: &Vec<char>) -> bool {
+    /// onto the x-axis (or y-axis, respectively) overlap. Returns an error if either row (or
+    /// column) does not contain exactly two marks.
+    fn are_interleaved(
+        &self,
+        row_or_column_a: &Vec<char>,
+        row_or_column_b: &Vec<char>,
+    ) -> Result<bool, &'static str> {
         // Find where the `x` and `o` occur in each row / column: `is_alphabetic()` returns `false`
         // for spaces
         let string_a = row_or_column_a.iter().collect::<String>();
@@ -315,69 +954,400 @@ impl Diagram {
         let matches_a: Vec<(usize, &str)> = string_a.match_indices(char::is_alphabetic).collect();
         let matches_b: Vec<(usize, &str)> = string_b.match_indices(char::is_alphabetic).collect();
 
-        assert_eq!(matches_a.len(), 2);
-        assert_eq!(matches_b.len(), 2);
+        if matches_a.len() != 2 || matches_b.len() != 2 {
+            return Err(
+                "Each row (or column) being exchanged must contain exactly one `x` and one `o`",
+            );
+        }
 
         let (a_start, a_end) = (matches_a[0].0, matches_a[1].0);
         let (b_start, b_end) = (matches_b[0].0, matches_b[1].0);
 
         if a_start > b_start && a_end < b_end {
             // `a` is completely contained in `b`
-            return false;
+            return Ok(false);
         } else if b_start > a_start && b_end < a_end {
             // `b` is completely contained in `a`
-            return false;
+            return Ok(false);
         } else if a_end < b_start {
             // `a` is totally "above" `b`
-            return false;
+            return Ok(false);
         } else if a_start > b_end {
             // `a` is totally "below" `b`
-            return false;
+            return Ok(false);
         } else if b_end < a_start {
             // `b` is totally "above" `a`
-            return false;
+            return Ok(false);
         } else if b_start > a_end {
             // `b` is totally "below" `a`
-            return false;
+            return Ok(false);
         }
 
         // `a` and `b` must be interleaved
-        true
+        Ok(true)
     }
 
-    /// Converts a pair of grid indices `<i, j>`, each of which lies in the range
-    /// `[0..self.resolution]`, to an "absolute" index, ranging from `[0..self.resolution^2]`.
-    fn convert_to_absolute_index(&self, i: usize, j: usize) -> usize {
-        i + j * self.resolution
+    /// Checks whether the vertical segment in column `j` (spanning the rows in `column_span`)
+    /// crosses the horizontal segment in row `i` (spanning the columns in `row_span`). Both
+    /// spans are expected to be sorted, i.e. `(start, end)` with `start < end`.
+    fn segments_cross(
+        j: usize,
+        column_span: (usize, usize),
+        i: usize,
+        row_span: (usize, usize),
+    ) -> bool {
+        j > row_span.0 && j < row_span.1 && i > column_span.0 && i < column_span.1
     }
 
-    /// Converts an "absolute index" in the range `[0..self.resolution^2]` to a
-    /// pair of grid indices `<i, j>`, each of which lies in the range `[0..self.resolution]`.
-    fn convert_to_grid_indices(&self, absolute_index: usize) -> (usize, usize) {
-        (
-            absolute_index % self.resolution,
-            absolute_index / self.resolution,
-        )
-    }
+    /// Returns all of the crossings in the projection of this grid diagram as a list of
+    /// `(i, j, sign)` tuples, where `<i, j>` are the grid indices of the crossing and `sign`
+    /// is either `+1` or `-1`. Columns are oriented from `x` to `o` and rows from `o` to `x`,
+    /// and the vertical strand always passes over the horizontal strand.
+    fn find_crossings(&self) -> Vec<(usize, usize, i32)> {
+        let mut crossings = vec![];
+
+        for j in 0..self.cols {
+            let column = self.get_column(j).iter().collect::<String>();
+            let (x_i, o_i) = match (column.find('x'), column.find('o')) {
+                (Some(x_i), Some(o_i)) => (x_i, o_i),
+                _ => continue,
+            };
 
-    /// Generates a knot corresponding to this grid diagram.
-    pub fn generate_knot(&self) -> Knot {
-        // We begin traversing the knot at the first column:
-        // `s` = "Start", (relative) index of the `x` in the first column (there will always be one)
-        // `e` = "End", (relative) index of the `o` in the first column (there will always be one)
-        let mut s = self
-            .get_column(0)
-            .iter()
-            .collect::<String>()
-            .find('x')
-            .unwrap();
-        let mut e = self
-            .get_column(0)
-            .iter()
-            .collect::<String>()
-            .find('o')
-            .unwrap();
-        let tie = s;
+            for i in 0..self.rows {
+                let row = self.get_row(i).iter().collect::<String>();
+                let (x_j, o_j) = match (row.find('x'), row.find('o')) {
+                    (Some(x_j), Some(o_j)) => (x_j, o_j),
+                    _ => continue,
+                };
+
+                if Diagram::segments_cross(
+                    j,
+                    (x_i.min(o_i), x_i.max(o_i)),
+                    i,
+                    (x_j.min(o_j), x_j.max(o_j)),
+                ) {
+                    // The sign of the crossing is the sign of the cross product between the
+                    // (over) vertical and (under) horizontal strands: note that the row indices
+                    // increase downwards
+                    let vertical = o_i as i32 - x_i as i32;
+                    let horizontal = x_j as i32 - o_j as i32;
+                    crossings.push((i, j, (vertical * horizontal).signum()));
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Returns the writhe of the projection of this grid diagram, i.e. the sum of the signs
+    /// of all of its crossings.
+    pub fn writhe(&self) -> i32 {
+        self.find_crossings().iter().map(|(_, _, sign)| sign).sum()
+    }
+
+    /// Splits this grid diagram into its components by following each strand around the grid
+    /// (columns from `x` to `o`, rows from `o` to `x`), returning the index of the component
+    /// that each row and each column belongs to, respectively. A knot has a single component,
+    /// while a link has several.
+    fn label_components(&self) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+        let mut row_labels = vec![None; self.rows];
+        let mut col_labels = vec![None; self.cols];
+
+        let mut label = 0;
+        for start in 0..self.cols {
+            if col_labels[start].is_some() {
+                continue;
+            }
+
+            let mut j = start;
+            loop {
+                col_labels[j] = Some(label);
+
+                // Follow this column to its `o`, then follow that row to its `x`
+                let i = match self.get_column(j).iter().position(|&c| c == 'o') {
+                    Some(i) => i,
+                    None => break,
+                };
+                row_labels[i] = Some(label);
+
+                j = match self.data[i].iter().position(|&c| c == 'x') {
+                    Some(j) => j,
+                    None => break,
+                };
+                if col_labels[j].is_some() {
+                    break;
+                }
+            }
+            label += 1;
+        }
+
+        (row_labels, col_labels)
+    }
+
+    /// Returns a closed polyline for each component of the link described by this grid
+    /// diagram (a knot has exactly one), embedded in 3-space in the same way as
+    /// `Diagram::generate_knot` (with the default `GenerateOptions`).
+    pub fn components(&self) -> Vec<Polyline> {
+        let opts = GenerateOptions::default();
+        let (_, col_labels) = self.label_components();
+        let crossings = self.find_crossings();
+
+        let w = self.cols as f32 * opts.scale;
+        let h = self.rows as f32 * opts.scale;
+        let to_world = |i: usize, j: usize, z: f32| {
+            Vector3::new(
+                j as f32 * opts.scale - 0.5 * w,
+                h - i as f32 * opts.scale - 0.5 * h,
+                z,
+            )
+        };
+
+        let count = col_labels
+            .iter()
+            .filter_map(|label| *label)
+            .max()
+            .map_or(0, |max| max + 1);
+        let mut components = vec![];
+        for label in 0..count {
+            let mut path = Polyline::new();
+
+            // Visit this component's columns in order: each column runs from its `x` to its
+            // `o`, passing over any rows that it crosses, and is joined to the next column by
+            // the row that contains its `o`
+            let mut j = match col_labels.iter().position(|l| *l == Some(label)) {
+                Some(j) => j,
+                None => continue,
+            };
+            loop {
+                let column = self.get_column(j);
+                let (x_i, o_i) = match (
+                    column.iter().position(|&c| c == 'x'),
+                    column.iter().position(|&c| c == 'o'),
+                ) {
+                    (Some(x_i), Some(o_i)) => (x_i, o_i),
+                    _ => break,
+                };
+
+                let mut lifted: Vec<usize> = crossings
+                    .iter()
+                    .filter(|(_, crossing_j, _)| *crossing_j == j)
+                    .map(|(i, _, _)| *i)
+                    .collect();
+                lifted.sort();
+                if o_i < x_i {
+                    lifted.reverse();
+                }
+
+                path.push_vertex(&to_world(x_i, j, 0.0));
+                for i in lifted.iter() {
+                    path.push_vertex(&to_world(*i, j, opts.lift_amount));
+                }
+                path.push_vertex(&to_world(o_i, j, 0.0));
+
+                j = match self.data[o_i].iter().position(|&c| c == 'x') {
+                    Some(next) if next != j && col_labels[next] == Some(label) => next,
+                    _ => break,
+                };
+                if col_labels.iter().position(|l| *l == Some(label)) == Some(j) {
+                    break;
+                }
+            }
+
+            components.push(path.refine(opts.refine_length));
+        }
+
+        components
+    }
+
+    /// Returns the linking number of the first two components of the link described by this
+    /// grid diagram, i.e. half the sum of the signs of the crossings between them, or `0` if
+    /// it has fewer than two components.
+    pub fn linking_number(&self) -> i32 {
+        let (row_labels, col_labels) = self.label_components();
+
+        let sum: i32 = self
+            .find_crossings()
+            .iter()
+            .filter(|(i, j, _)| match (row_labels[*i], col_labels[*j]) {
+                (Some(0), Some(1)) | (Some(1), Some(0)) => true,
+                _ => false,
+            })
+            .map(|(_, _, sign)| sign)
+            .sum();
+
+        sum / 2
+    }
+
+    /// Counts the corners of the grid projection that become cusps in the corresponding
+    /// Legendrian front, which is obtained by rotating the grid 45 degrees counterclockwise
+    /// (so that the vertical strands have a smaller slope than the horizontal strands and
+    /// thus pass in front of them). Only NW and SE corners become cusps. Returns the number
+    /// of cusps that are traversed downwards and upwards, respectively.
+    fn count_cusps(&self) -> (i32, i32) {
+        let (mut down, mut up) = (0, 0);
+
+        for ((i, j), mark) in self.marks() {
+            // Find the other mark in this row and column, respectively
+            let other = if mark == 'x' { 'o' } else { 'x' };
+            let partner_j = self.data[i].iter().position(|&c| c == other);
+            let partner_i = self.get_column(j).iter().position(|&c| c == other);
+            let (partner_i, partner_j) = match (partner_i, partner_j) {
+                (Some(partner_i), Some(partner_j)) => (partner_i, partner_j),
+                _ => continue,
+            };
+
+            let points_east = partner_j > j;
+            let points_south = partner_i > i;
+
+            // Columns are traversed from `x` to `o` and rows from `o` to `x`, so each `x`
+            // is entered horizontally and each `o` is entered vertically: at a left cusp
+            // (NW corner) the strand travels upwards if it is entered from below, while
+            // at a right cusp (SE corner) it travels upwards if it is entered from the left
+            match (points_east, points_south, mark) {
+                (true, true, 'x') | (false, false, 'o') => down += 1,
+                (true, true, 'o') | (false, false, 'x') => up += 1,
+                _ => (),
+            }
+        }
+        (down, up)
+    }
+
+    /// Returns the Thurston-Bennequin number of the Legendrian knot corresponding to this
+    /// grid diagram, i.e. the writhe of its front minus half the number of cusps.
+    pub fn thurston_bennequin(&self) -> i32 {
+        let (down, up) = self.count_cusps();
+        self.writhe() - (down + up) / 2
+    }
+
+    /// Returns the rotation number of the Legendrian knot corresponding to this grid
+    /// diagram, i.e. half the difference between the number of downward and upward cusps.
+    pub fn rotation_number(&self) -> i32 {
+        let (down, up) = self.count_cusps();
+        (down - up) / 2
+    }
+
+    /// Returns the winding number of the knot around the lattice point (i.e. the corner
+    /// shared by four cells) at the top-left corner of the cell `<i, j>`. This is the signed
+    /// number of vertical segments crossed by a ray that travels leftwards from the point.
+    fn winding_number(&self, i: usize, j: usize) -> i32 {
+        let mut winding = 0;
+        for column in 0..j {
+            let entries = self.get_column(column);
+            let x_i = entries.iter().position(|&c| c == 'x');
+            let o_i = entries.iter().position(|&c| c == 'o');
+
+            if let (Some(x_i), Some(o_i)) = (x_i, o_i) {
+                // The segment passes through the centers of the cells between the two marks
+                if x_i.min(o_i) < i && i <= x_i.max(o_i) {
+                    winding += if o_i > x_i { 1 } else { -1 };
+                }
+            }
+        }
+        winding
+    }
+
+    /// Returns the coefficients of the Alexander polynomial of the knot corresponding to
+    /// this grid diagram, starting with the constant term. The polynomial is normalized so
+    /// that its constant term is non-zero and (for knots) its coefficients sum to `1`. For
+    /// example, the trefoil has the Alexander polynomial `1 - t + t²`, i.e. `[1, -1, 1]`. An
+    /// empty vector is returned for rectangular grids and split links (whose polynomial
+    /// vanishes).
+    ///
+    /// The polynomial is computed from the `n`x`n` matrix whose entry at `<i, j>` is `t^-a`,
+    /// where `a` is the winding number of the knot around the top-left corner of cell `<i, j>`.
+    /// The determinant of this matrix is `±t^k (1 - t)^(n - 1)` times the Alexander polynomial.
+    ///
+    /// Reference: `https://arxiv.org/pdf/math/0607691.pdf`
+    pub fn alexander_polynomial(&self) -> Vec<i32> {
+        if !self.is_square() || self.rows == 0 {
+            return vec![];
+        }
+        let n = self.rows;
+
+        // Offset every exponent so that they are all non-negative: this only multiplies the
+        // determinant by a power of `t`, which is removed during normalization
+        let winding: Vec<Vec<i32>> = (0..n)
+            .map(|i| (0..n).map(|j| self.winding_number(i, j)).collect())
+            .collect();
+        let max_winding = winding.iter().flatten().cloned().max().unwrap_or(0);
+        let matrix = winding
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|a| {
+                        let mut monomial = vec![0; (max_winding - a) as usize + 1];
+                        *monomial.last_mut().unwrap() = 1;
+                        monomial
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut polynomial = polynomial_determinant(matrix);
+        for _ in 0..n - 1 {
+            polynomial = polynomial_divide(&polynomial, &vec![1, -1]);
+        }
+        normalize_alexander_polynomial(&polynomial)
+    }
+
+    /// Converts a pair of grid indices `<i, j>`, which lie in the ranges `[0..self.rows]`
+    /// and `[0..self.cols]`, to an "absolute" index, ranging from `[0..self.rows * self.cols]`.
+    fn convert_to_absolute_index(&self, i: usize, j: usize) -> usize {
+        i + j * self.rows
+    }
+
+    /// Converts an "absolute index" in the range `[0..self.rows * self.cols]` to a pair of
+    /// grid indices `<i, j>`, which lie in the ranges `[0..self.rows]` and `[0..self.cols]`.
+    fn convert_to_grid_indices(&self, absolute_index: usize) -> (usize, usize) {
+        (absolute_index % self.rows, absolute_index / self.rows)
+    }
+
+    /// Generates a knot corresponding to this grid diagram, which must be square and must
+    /// describe a knot (rather than a link with several components).
+    pub fn generate_knot(&self) -> Result<Knot, &'static str> {
+        self.generate_knot_with(&GenerateOptions::default())
+    }
+
+    /// Generates a knot corresponding to this grid diagram (see `Diagram::generate_knot`),
+    /// using `opts` to control its size and the height of its crossings.
+    pub fn generate_knot_with(&self, opts: &GenerateOptions) -> Result<Knot, &'static str> {
+        let path = self.generate_path_with(opts)?;
+        println!(
+            "Total vertices in refined path: {}",
+            path.get_number_of_vertices()
+        );
+
+        let mut knot = Knot::new(&path, None);
+        knot.set_diagram(self.clone(), *opts);
+        Ok(knot)
+    }
+
+    /// Generates the (refined) polyline traced out by the knot corresponding to this grid
+    /// diagram, without building a `Knot` around it. See `Diagram::generate_knot_with`.
+    pub fn generate_path_with(&self, opts: &GenerateOptions) -> Result<Polyline, &'static str> {
+        if !self.is_square() {
+            return Err("Cannot generate a knot from a rectangular grid diagram: grid diagrams must be square");
+        }
+        if self.rows == 0 {
+            return Err("Cannot generate a knot from an empty grid diagram");
+        }
+
+        // We begin traversing the knot at the first column:
+        // `s` = "Start", (relative) index of the `x` in the first column
+        // `e` = "End", (relative) index of the `o` in the first column
+        let missing_mark = "Every row and column of a grid diagram must contain an `x` and an `o`";
+        let mut s = self
+            .get_column(0)
+            .iter()
+            .collect::<String>()
+            .find('x')
+            .ok_or(missing_mark)?;
+        let mut e = self
+            .get_column(0)
+            .iter()
+            .collect::<String>()
+            .find('o')
+            .ok_or(missing_mark)?;
+        let tie = s;
 
         let mut knot_topology = vec![
             self.convert_to_absolute_index(s, 0),
@@ -396,14 +1366,28 @@ impl Diagram {
             let (next_index, slice) = if traverse_horizontal {
                 // We just found an `o` (in the last column), so find the `x` in this row
                 let slice = self.get_row(e);
-                (slice.iter().collect::<String>().find('x').unwrap(), slice)
+                (
+                    slice
+                        .iter()
+                        .collect::<String>()
+                        .find('x')
+                        .ok_or(missing_mark)?,
+                    slice,
+                )
             } else {
                 // We just found an `x` (in the last row), so find the `o` in this column
                 let slice = self.get_column(e);
-                (slice.iter().collect::<String>().find('o').unwrap(), slice)
+                (
+                    slice
+                        .iter()
+                        .collect::<String>()
+                        .find('o')
+                        .ok_or(missing_mark)?,
+                    slice,
+                )
             };
 
-            // Convert the above index to absolute indices that range from `[0..(self.resolution * self.resolution)]`,
+            // Convert the above index to absolute indices that range from `[0..(self.rows * self.cols)]`,
             // taking care to modify the function parameters based on the current orientation (horizontal / vertical)
             let absolute_index = if traverse_horizontal {
                 self.convert_to_absolute_index(e, next_index)
@@ -439,10 +1423,13 @@ impl Diagram {
         //            knot_topology
         //        );
 
-        // This should always be true, i.e. for a 6x6 grid there should be 6 pairs of x's and o's (12
-        // indices total)...note that we perform this check before checking for any crossings, which
-        // will necessarily add more indices to the knot topology
-        assert_eq!(knot_topology.len(), self.resolution * 2 + 1);
+        // For a knot, this should always be true, i.e. for a 6x6 grid there should be 6 pairs of x's
+        // and o's (12 indices total)...note that we perform this check before checking for any crossings,
+        // which will necessarily add more indices to the knot topology: if the traversal returned to
+        // its starting point early, the grid diagram describes a link
+        if knot_topology.len() != self.cols * 2 + 1 {
+            return Err("This grid diagram describes a link with more than one component: only knots can be generated");
+        }
 
         // Find crossings: rows pass under any columns that they intersect, so we will
         // add additional vertex (or vertices) to any column that contains a intersection(s)
@@ -478,7 +1465,7 @@ impl Diagram {
                 let (rs_i, rs_j) = self.convert_to_grid_indices(row_s);
                 let (re_i, re_j) = self.convert_to_grid_indices(row_e);
 
-                if cs_j > rs_j && cs_j < re_j && cs_i < rs_i && ce_i > rs_i {
+                if Diagram::segments_cross(cs_j, (cs_i, ce_i), rs_i, (rs_j, re_j)) {
                     let intersect = self.convert_to_absolute_index(rs_i, cs_j);
                     intersections.push((rs_i, intersect));
                     lifted.push(intersect);
@@ -520,26 +1507,31 @@ impl Diagram {
         // Convert indices to actual 3D positions so that we can
         // (eventually) draw a polyline corresponding to this knot: the
         // world-space width and height of the 3D grid are automatically
-        // set to the resolution of the diagram so that each grid "cell"
-        // is unit width / height
+        // set to the dimensions of the diagram so that each grid "cell"
+        // is `opts.scale` units wide / tall
         let mut path = Polyline::new();
-        let w = self.resolution as f32;
-        let h = self.resolution as f32;
+        let w = self.cols as f32 * opts.scale;
+        let h = self.rows as f32 * opts.scale;
 
-        // This value is somewhat arbitrary but should *probably* match
+        // The default lift is somewhat arbitrary but should *probably* match
         // the tube radius used later on in the rendering loop...
-        let lift_amount = 0.1;
+        //
+        // Note that the lift is always positive: by convention, vertical strands pass over
+        // horizontal strands in a grid diagram, and the sign of each crossing already follows
+        // from the orientations of the two strands in the plane, so mirror-image diagrams
+        // produce mirror-image knots
+        let lift_amount = opts.lift_amount;
 
         for absolute_index in knot_topology.iter() {
             // Remember:
-            // `i` is the row, ranging from `[0..self.resolution]`
-            // `j` is the col, ranging from `[0..self.resolution]`
+            // `i` is the row, ranging from `[0..self.rows]`
+            // `j` is the col, ranging from `[0..self.cols]`
             let (i, j) = self.convert_to_grid_indices(*absolute_index);
 
             // World-space position of the vertex corresponding to this grid index:
             // make sure that the center of the grid lies at the origin
-            let x = (j as f32 / self.resolution as f32) * w - 0.5 * w;
-            let y = h - (i as f32 / self.resolution as f32) * h - 0.5 * h;
+            let x = (j as f32 / self.cols as f32) * w - 0.5 * w;
+            let y = h - (i as f32 / self.rows as f32) * h - 0.5 * h;
             let z = if lifted.contains(absolute_index) {
                 lift_amount
             } else {
@@ -550,16 +1542,77 @@ impl Diagram {
         }
 
         // Subdivide the path
-        path = path.refine(0.5);
-        println!(
-            "Total vertices in refined path: {}",
-            path.get_number_of_vertices()
-        );
+        Ok(path.refine(opts.refine_length))
+    }
+}
+
+/// A helper for building a square grid diagram mark-by-mark (e.g. `DiagramBuilder::new(2)
+/// .set_x(0, 0).set_o(0, 1).set_o(1, 0).set_x(1, 1).build()` is the unknot), rather than
+/// from a file or a 2D array of `char`s.
+pub struct DiagramBuilder {
+    // The number of rows (and columns) in the grid diagram
+    resolution: usize,
+
+    // The grid diagram's entries, which start out blank
+    data: Vec<Vec<char>>,
 
-        Knot::new(&path, None)
+    // The first error encountered while placing marks, if any (reported by `build`)
+    error: Option<String>,
+}
+
+impl DiagramBuilder {
+    /// Starts building a blank `resolution`x`resolution` grid diagram.
+    pub fn new(resolution: usize) -> DiagramBuilder {
+        DiagramBuilder {
+            resolution,
+            data: vec![vec![' '; resolution]; resolution],
+            error: None,
+        }
+    }
+
+    /// Places an `x` in row `i` and column `j`.
+    pub fn set_x(self, i: usize, j: usize) -> DiagramBuilder {
+        self.set(i, j, 'x')
+    }
+
+    /// Places an `o` in row `i` and column `j`.
+    pub fn set_o(self, i: usize, j: usize) -> DiagramBuilder {
+        self.set(i, j, 'o')
+    }
+
+    /// Places `mark` in row `i` and column `j`, remembering an error if the indices are out
+    /// of range.
+    fn set(mut self, i: usize, j: usize, mark: char) -> DiagramBuilder {
+        if i < self.resolution && j < self.resolution {
+            self.data[i][j] = mark;
+        } else if self.error.is_none() {
+            self.error = Some(format!(
+                "Cannot place '{}' at row {}, column {} of a {}x{} grid diagram",
+                mark, i, j, self.resolution, self.resolution
+            ));
+        }
+        self
+    }
+
+    /// Builds (and validates) the grid diagram.
+    pub fn build(self) -> Result<Diagram, String> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Diagram::from_data(self.data)
     }
 }
 
+impl PartialEq for Diagram {
+    /// Two grid diagrams are equal if their grids are identical: the history of Cromwell
+    /// moves that produced each of them is ignored.
+    fn eq(&self, other: &Diagram) -> bool {
+        self.rows == other.rows && self.cols == other.cols && self.data == other.data
+    }
+}
+
+impl Eq for Diagram {}
+
 impl std::fmt::Debug for Diagram {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for row in self.data.iter() {
@@ -568,3 +1621,768 @@ impl std::fmt::Debug for Diagram {
         Ok(())
     }
 }
+
+/// Trims any zero coefficients from the end (i.e. the highest degree terms) of `polynomial`.
+fn polynomial_trim(mut polynomial: Vec<i64>) -> Vec<i64> {
+    while polynomial.len() > 1 && *polynomial.last().unwrap() == 0 {
+        polynomial.pop();
+    }
+    polynomial
+}
+
+/// Returns `true` if every coefficient of `polynomial` is zero.
+fn polynomial_is_zero(polynomial: &Vec<i64>) -> bool {
+    polynomial.iter().all(|&c| c == 0)
+}
+
+/// Multiplies two polynomials, each of which is stored as a list of (integer) coefficients
+/// starting with the constant term.
+fn polynomial_multiply(a: &Vec<i64>, b: &Vec<i64>) -> Vec<i64> {
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, coefficient_a) in a.iter().enumerate() {
+        for (j, coefficient_b) in b.iter().enumerate() {
+            product[i + j] += coefficient_a * coefficient_b;
+        }
+    }
+    polynomial_trim(product)
+}
+
+/// Subtracts polynomial `b` from polynomial `a`.
+fn polynomial_subtract(a: &Vec<i64>, b: &Vec<i64>) -> Vec<i64> {
+    let mut difference = vec![0; a.len().max(b.len())];
+    for (i, coefficient) in a.iter().enumerate() {
+        difference[i] += coefficient;
+    }
+    for (i, coefficient) in b.iter().enumerate() {
+        difference[i] -= coefficient;
+    }
+    polynomial_trim(difference)
+}
+
+/// Divides polynomial `a` by polynomial `b` (which must be non-zero) via long division,
+/// assuming that `b` divides `a` exactly over the integers.
+fn polynomial_divide(a: &Vec<i64>, b: &Vec<i64>) -> Vec<i64> {
+    let a = polynomial_trim(a.clone());
+    let b = polynomial_trim(b.clone());
+    if polynomial_is_zero(&a) || a.len() < b.len() {
+        return vec![0];
+    }
+
+    let mut remainder = a.clone();
+    let mut quotient = vec![0; a.len() - b.len() + 1];
+    let leading = *b.last().unwrap();
+
+    for degree in (0..quotient.len()).rev() {
+        let coefficient = remainder[degree + b.len() - 1] / leading;
+        quotient[degree] = coefficient;
+        for (i, coefficient_b) in b.iter().enumerate() {
+            remainder[degree + i] -= coefficient * coefficient_b;
+        }
+    }
+    polynomial_trim(quotient)
+}
+
+/// Normalizes an Alexander polynomial, which is only defined up to multiplication by `±t^k`:
+/// factors of `t` are stripped and the sign is chosen so that the polynomial evaluates to `1`
+/// at `t = 1`. An empty vector is returned if `polynomial` vanishes.
+pub fn normalize_alexander_polynomial(polynomial: &Vec<i64>) -> Vec<i32> {
+    let first = match polynomial.iter().position(|&c| c != 0) {
+        Some(first) => first,
+        None => return vec![],
+    };
+    let last = polynomial.iter().rposition(|&c| c != 0).unwrap();
+    let sign = match polynomial.iter().sum::<i64>().signum() {
+        // The polynomial of a link may vanish at `t = 1`
+        0 => polynomial[first].signum(),
+        sign => sign,
+    };
+
+    polynomial[first..=last]
+        .iter()
+        .map(|c| (c * sign) as i32)
+        .collect()
+}
+
+/// Computes the determinant of a square matrix whose entries are polynomials, using the
+/// fraction-free Bareiss algorithm (so that every intermediate division is exact).
+pub fn polynomial_determinant(mut matrix: Vec<Vec<Vec<i64>>>) -> Vec<i64> {
+    let n = matrix.len();
+    let mut sign = 1;
+    let mut previous_pivot = vec![1];
+
+    for k in 0..n.saturating_sub(1) {
+        // Find a non-zero pivot, swapping rows if necessary
+        if polynomial_is_zero(&matrix[k][k]) {
+            match (k + 1..n).find(|&row| !polynomial_is_zero(&matrix[row][k])) {
+                Some(row) => {
+                    matrix.swap(k, row);
+                    sign = -sign;
+                }
+                None => return vec![0],
+            }
+        }
+
+        for i in k + 1..n {
+            for j in k + 1..n {
+                let numerator = polynomial_subtract(
+                    &polynomial_multiply(&matrix[k][k], &matrix[i][j]),
+                    &polynomial_multiply(&matrix[i][k], &matrix[k][j]),
+                );
+                matrix[i][j] = polynomial_divide(&numerator, &previous_pivot);
+            }
+        }
+        previous_pivot = matrix[k][k].clone();
+    }
+
+    match n {
+        0 => vec![1],
+        _ => matrix[n - 1][n - 1].iter().map(|c| c * sign).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EPSILON;
+    use crate::tangle::Tangle;
+    use crate::utils::approx_eq;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn minimize_removes_stabilizations() {
+        let mut diagram = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let (i, j) = diagram.x_positions()[0];
+        diagram
+            .apply_move(CromwellMove::Stabilization {
+                cardinality: Cardinality::NE,
+                i,
+                j,
+            })
+            .unwrap();
+        assert_eq!(diagram.arc_index(), 6);
+
+        assert_eq!(diagram.minimize(), 5);
+        assert_eq!(diagram.history.len(), 2);
+        assert_eq!(diagram.generate_knot().unwrap().identify(), Some("3_1"));
+    }
+
+    #[test]
+    fn mirror_negates_the_writhe() {
+        for name in ["trefoil", "figure_eight", "legendrian"].iter() {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            let n = diagram.arc_index() as i32;
+
+            for axis in [Axis::Row, Axis::Column].iter() {
+                let mut mirrored = diagram.clone();
+                mirrored.mirror(*axis);
+                assert_eq!(mirrored.writhe(), -diagram.writhe(), "{}", name);
+                assert_eq!(
+                    mirrored.thurston_bennequin() + diagram.thurston_bennequin(),
+                    -n,
+                    "{}",
+                    name
+                );
+
+                mirrored.mirror(*axis);
+                assert_eq!(mirrored.get_data(), diagram.get_data(), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn transpose_twice_is_the_identity() {
+        let diagram = Diagram::from_path(Path::new("diagrams/legendrian.csv")).unwrap();
+        let mut transposed = diagram.clone();
+        transposed.transpose();
+        assert_ne!(transposed.get_data(), diagram.get_data());
+        assert_eq!(transposed.writhe(), diagram.writhe());
+
+        transposed.transpose();
+        assert_eq!(transposed.get_data(), diagram.get_data());
+
+        // Rectangular grids swap their dimensions
+        let mut partial = Diagram::from_data(vec![vec!['x', 'o', ' ']]).unwrap();
+        partial.transpose();
+        assert_eq!(partial.get_number_of_rows(), 3);
+        assert_eq!(partial.get_number_of_columns(), 1);
+        partial.transpose();
+        assert_eq!(partial.get_data(), &vec![vec!['x', 'o', ' ']]);
+    }
+
+    #[test]
+    fn gauss_code_of_the_trefoil() {
+        let diagram = Diagram::from_gauss_code("O1+U2+O3+U1+O2+U3+").unwrap();
+        let knot = diagram.generate_knot().unwrap();
+        assert_eq!(knot.get_number_of_crossings(), 3);
+        assert!(knot
+            .find_crossings()
+            .iter()
+            .all(|crossing| crossing.sign == 1));
+        assert_eq!(knot.identify(), Some("3_1"));
+    }
+
+    #[test]
+    fn gauss_code_of_the_figure_eight() {
+        let diagram = Diagram::from_gauss_code("U1-O2+U3+O1-U4-O3+U2+O4-").unwrap();
+        let knot = diagram.generate_knot().unwrap();
+        assert_eq!(knot.get_number_of_crossings(), 4);
+        assert_eq!(knot.identify(), Some("4_1"));
+    }
+
+    #[test]
+    fn gauss_code_of_the_unknot() {
+        let diagram = Diagram::from_gauss_code("").unwrap();
+        assert_eq!(diagram.get_resolution(), 2);
+
+        // A single kink is nugatory
+        let diagram = Diagram::from_gauss_code("O1-U1-").unwrap();
+        assert_eq!(diagram.get_resolution(), 2);
+    }
+
+    #[test]
+    fn invalid_gauss_codes() {
+        assert!(Diagram::from_gauss_code("O1+U2+O3+U1+O2+").is_err());
+        assert!(Diagram::from_gauss_code("O1+O2+U1+U2+").is_err());
+        assert!(Diagram::from_gauss_code("O1+U1-").is_err());
+        assert!(Diagram::from_gauss_code("X1+U1+").is_err());
+        assert!(Diagram::from_gauss_code("O1U1").is_err());
+    }
+
+    #[test]
+    fn apply_sequence_reports_the_failing_index() {
+        let mut diagram = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let moves = vec![
+            CromwellMove::Translation(Direction::Up),
+            CromwellMove::Commutation {
+                axis: Axis::Row,
+                start_index: 99,
+            },
+            CromwellMove::Translation(Direction::Down),
+        ];
+
+        let error = diagram.apply_sequence(moves).unwrap_err();
+        assert!(
+            error.starts_with("Cromwell move at index 1 failed"),
+            "{}",
+            error
+        );
+
+        // Only the first move was applied
+        assert_eq!(diagram.history.len(), 1);
+    }
+
+    #[test]
+    fn undo_restores_the_original_grid() {
+        let original = Diagram::from_path(Path::new("diagrams/figure_eight.csv")).unwrap();
+        let mut diagram = original.clone();
+        let (i, j) = diagram.x_positions()[0];
+        diagram
+            .apply_sequence(vec![
+                CromwellMove::Stabilization {
+                    cardinality: Cardinality::SE,
+                    i,
+                    j,
+                },
+                CromwellMove::Translation(Direction::Left),
+                CromwellMove::Translation(Direction::Down),
+            ])
+            .unwrap();
+        assert_eq!(diagram.get_resolution(), 7);
+
+        for _ in 0..3 {
+            diagram.undo().unwrap();
+        }
+        assert_eq!(diagram.get_data(), original.get_data());
+        assert_eq!(diagram.get_resolution(), 6);
+        assert!(diagram.undo().is_err());
+    }
+
+    /// Returns a 4x4 grid whose last row (`o` and `x` in the outer columns) surrounds its first
+    /// row (`x` and `o` in the inner columns), so the two can be exchanged.
+    fn nested_grid() -> Diagram {
+        Diagram::from_data(vec![
+            vec![' ', 'x', 'o', ' '],
+            vec!['x', 'o', ' ', ' '],
+            vec![' ', ' ', 'x', 'o'],
+            vec!['o', ' ', ' ', 'x'],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn commutation_wraps_around() {
+        let mut diagram = nested_grid();
+        let (first, last) = (diagram.get_row(0), diagram.get_row(3));
+        diagram
+            .apply_move(CromwellMove::Commutation {
+                axis: Axis::Row,
+                start_index: 3,
+            })
+            .unwrap();
+        assert_eq!(diagram.get_row(0), last);
+        assert_eq!(diagram.get_row(3), first);
+
+        // The first and last rows of the trefoil are interleaved
+        let mut trefoil = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        assert!(trefoil
+            .apply_move(CromwellMove::Commutation {
+                axis: Axis::Row,
+                start_index: 4,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn column_commutation_wraps_around() {
+        let mut diagram = nested_grid();
+        diagram.transpose();
+        let (first, last) = (diagram.get_column(0), diagram.get_column(3));
+        diagram
+            .apply_move(CromwellMove::Commutation {
+                axis: Axis::Column,
+                start_index: 3,
+            })
+            .unwrap();
+        assert_eq!(diagram.get_column(0), last);
+        assert_eq!(diagram.get_column(3), first);
+    }
+
+    #[test]
+    fn are_interleaved_rejects_malformed_rows() {
+        let diagram = nested_grid();
+        let (a, b) = (vec![' ', 'x', 'o', ' '], vec!['o', ' ', ' ', 'x']);
+        assert_eq!(diagram.are_interleaved(&a, &b), Ok(false));
+        assert_eq!(
+            diagram.are_interleaved(&vec![' ', 'x', ' ', 'o'], &vec!['x', ' ', 'o', ' ']),
+            Ok(true)
+        );
+        assert!(diagram
+            .are_interleaved(&a, &vec![' ', ' ', ' ', 'x'])
+            .is_err());
+    }
+
+    #[test]
+    fn can_apply_agrees_with_apply_move() {
+        let cardinalities = [
+            Cardinality::NW,
+            Cardinality::NE,
+            Cardinality::SW,
+            Cardinality::SE,
+        ];
+
+        for name in ["trefoil", "legendrian"].iter() {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            let n = diagram.get_resolution();
+
+            let mut moves = vec![CromwellMove::Translation(Direction::Up)];
+            for start_index in 0..n + 1 {
+                for axis in [Axis::Row, Axis::Column].iter() {
+                    moves.push(CromwellMove::Commutation {
+                        axis: *axis,
+                        start_index,
+                    });
+                }
+            }
+            for i in 0..n {
+                for j in 0..n {
+                    for cardinality in cardinalities.iter() {
+                        moves.push(CromwellMove::Stabilization {
+                            cardinality: *cardinality,
+                            i,
+                            j,
+                        });
+                        moves.push(CromwellMove::Destabilization {
+                            cardinality: *cardinality,
+                            i,
+                            j,
+                        });
+                    }
+                }
+            }
+
+            for cromwell in moves {
+                let mut trial = diagram.clone();
+                assert_eq!(
+                    diagram.can_apply(&cromwell),
+                    trial.apply_move(cromwell).is_ok(),
+                    "{} {:?}",
+                    name,
+                    cromwell
+                );
+                assert_eq!(diagram.history.len(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn writhe_matches_the_crossing_signs() {
+        for (name, writhe) in [("trefoil", 3), ("figure_eight", -1), ("legendrian", 6)].iter() {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            assert_eq!(diagram.writhe(), *writhe, "{}", name);
+
+            let knot = diagram.generate_knot().unwrap();
+            let signs: i32 = knot
+                .find_crossings()
+                .iter()
+                .map(|crossing| crossing.sign)
+                .sum();
+            assert_eq!(signs, *writhe, "{}", name);
+        }
+    }
+
+    #[test]
+    fn legendrian_invariants() {
+        // The bundled trefoil and 5_2 knot (`legendrian.csv`) realize the maximal
+        // Thurston-Bennequin numbers of their knot types
+        for (name, tb, rotation) in [
+            ("trefoil", 1, 0),
+            ("figure_eight", -3, 0),
+            ("legendrian", 1, 0),
+        ]
+        .iter()
+        {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            assert_eq!(diagram.thurston_bennequin(), *tb, "{}", name);
+            assert_eq!(diagram.rotation_number(), *rotation, "{}", name);
+
+            // Translations are Legendrian isotopies
+            let mut translated = diagram.clone();
+            translated
+                .apply_move(CromwellMove::Translation(Direction::Right))
+                .unwrap();
+            assert_eq!(translated.thurston_bennequin(), *tb, "{}", name);
+            assert_eq!(translated.rotation_number(), *rotation, "{}", name);
+        }
+    }
+
+    #[test]
+    fn alexander_polynomials_of_the_bundled_diagrams() {
+        for (name, polynomial) in [
+            ("trefoil", vec![1, -1, 1]),
+            ("figure_eight", vec![-1, 3, -1]),
+            ("legendrian", vec![2, -3, 2]),
+        ]
+        .iter()
+        {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            assert_eq!(diagram.alexander_polynomial(), *polynomial, "{}", name);
+        }
+
+        // The polynomial vanishes for split links and isn't defined for rectangular grids
+        let unlink = Diagram::from_data(vec![
+            vec!['x', 'o', ' ', ' '],
+            vec!['o', 'x', ' ', ' '],
+            vec![' ', ' ', 'x', 'o'],
+            vec![' ', ' ', 'o', 'x'],
+        ])
+        .unwrap();
+        assert!(unlink.alexander_polynomial().is_empty());
+        assert!(Diagram::from_data(vec![vec!['x', 'o', ' ']])
+            .unwrap()
+            .alexander_polynomial()
+            .is_empty());
+    }
+
+    #[test]
+    fn normalizes_alexander_polynomials() {
+        // `-t^2 + t - 1` becomes `1 - t + t^2`, and leading zeros are dropped
+        assert_eq!(
+            normalize_alexander_polynomial(&vec![0, -1, 1, -1]),
+            vec![1, -1, 1]
+        );
+        assert_eq!(
+            normalize_alexander_polynomial(&vec![0, 0]),
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn validation_reports_the_offending_entry() {
+        let error = Diagram::from_data(vec![vec!['x', 'q'], vec!['o', 'x']]).unwrap_err();
+        assert!(error.contains("row 0, column 1"), "{}", error);
+        assert!(error.contains("'q'"), "{}", error);
+
+        let error = Diagram::from_data(vec![vec!['x', 'x'], vec!['o', 'o']]).unwrap_err();
+        assert!(error.contains("row 0 contains 2 'x' marks"), "{}", error);
+
+        let error = Diagram::from_data(vec![vec!['x', 'o'], vec!['x', 'o']]).unwrap_err();
+        assert!(error.contains("column 0 contains 2 'x' marks"), "{}", error);
+    }
+
+    #[test]
+    fn from_path_reports_malformed_files() {
+        assert!(Diagram::from_path(Path::new("diagrams/trefoil.txt")).is_err());
+        assert!(Diagram::from_path(Path::new("diagrams/missing.csv")).is_err());
+
+        let directory = std::env::temp_dir().join("knots_from_path");
+        std::fs::create_dir_all(&directory).unwrap();
+        let read = |name: &str, contents: &str| {
+            let path = directory.join(name);
+            std::fs::write(&path, contents).unwrap();
+            Diagram::from_path(&path)
+        };
+
+        let error = read("ragged.csv", "x,o\no\n").unwrap_err();
+        assert!(
+            error.contains("Row 1 of grid file has 1 columns"),
+            "{}",
+            error
+        );
+
+        let error = read("wide.csv", "x,o\nox, \n").unwrap_err();
+        assert!(error.contains("Row 1, column 0"), "{}", error);
+
+        // Empty fields are blank
+        let diagram = read("blank.csv", "x,o,\n,x,o\no,,x\n").unwrap();
+        assert_eq!(diagram.get(0, 2), Some(' '));
+    }
+
+    #[test]
+    fn rectangular_grids() {
+        let partial = Diagram::from_data(vec![vec!['x', ' ', 'o'], vec![' ', 'x', ' ']]).unwrap();
+        assert!(!partial.is_square());
+        assert_eq!(partial.get_number_of_rows(), 2);
+        assert_eq!(partial.get_number_of_columns(), 3);
+        assert!(partial.generate_knot().is_err());
+        assert!(!partial.can_apply(&CromwellMove::Commutation {
+            axis: Axis::Row,
+            start_index: 0,
+        }));
+
+        // Ragged grids (and duplicate marks) are still rejected
+        assert!(Diagram::from_data(vec![vec!['x', 'o'], vec![' ']]).is_err());
+        assert!(Diagram::from_data(vec![vec!['x', 'x', 'o']]).is_err());
+    }
+
+    #[test]
+    fn seeded_random_walks_are_deterministic() {
+        let start = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let walk = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut diagram = start.clone();
+            let mut moves = vec![];
+            for _ in 0..1000 {
+                let history = diagram.history.len();
+                moves.push(format!("{:?}", diagram.random_move(&mut rng).unwrap()));
+                assert_eq!(diagram.history.len(), history + 1);
+                assert!(diagram.validate().is_ok());
+
+                // Stabilizations make up most of the legal moves, so shrink the grid every so
+                // often to keep each step (and the invariants below) cheap to compute
+                if diagram.get_resolution() > 12 {
+                    diagram.minimize();
+                    diagram.history.clear();
+                }
+            }
+            (moves, diagram)
+        };
+
+        let (moves, diagram) = walk(7);
+        let (same_moves, same_diagram) = walk(7);
+        assert_eq!(moves, same_moves);
+        assert_eq!(diagram.get_data(), same_diagram.get_data());
+
+        // Neither the random moves nor the destabilizations ever change the knot type
+        assert_eq!(diagram.components().len(), 1);
+        assert_eq!(diagram.alexander_polynomial(), start.alexander_polynomial());
+    }
+
+    #[test]
+    fn sampling_produces_every_variant() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut directions = [false; 4];
+        let mut axes = [false; 2];
+        let mut cardinalities = [false; 4];
+        for _ in 0..200 {
+            directions[rng.gen::<Direction>() as usize] = true;
+            axes[rng.gen::<Axis>() as usize] = true;
+            cardinalities[rng.gen::<Cardinality>() as usize] = true;
+        }
+        assert!(directions.iter().all(|&seen| seen));
+        assert!(axes.iter().all(|&seen| seen));
+        assert!(cardinalities.iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn translations_share_a_canonical_form() {
+        let original = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let mut translated = original.clone();
+        translated
+            .apply_move(CromwellMove::Translation(Direction::Down))
+            .unwrap()
+            .apply_move(CromwellMove::Translation(Direction::Down))
+            .unwrap();
+
+        // Equality ignores the history, but not the position of the marks
+        assert_ne!(translated, original);
+        assert_eq!(translated.canonicalize(), original.canonicalize());
+        assert!(translated.canonicalize().history.is_empty());
+
+        let mut undone = translated.clone();
+        undone.undo().unwrap().undo().unwrap();
+        assert_eq!(undone, original);
+
+        let figure_eight = Diagram::from_path(Path::new("diagrams/figure_eight.csv")).unwrap();
+        assert_ne!(figure_eight.canonicalize(), original.canonicalize());
+    }
+
+    #[test]
+    fn get_and_set_are_bounds_checked() {
+        let mut diagram = nested_grid();
+        assert_eq!(diagram.get(0, 1), Some('x'));
+        assert_eq!(diagram.get(0, 0), Some(' '));
+        assert_eq!(diagram.get(4, 0), None);
+        assert_eq!(diagram.get(0, 4), None);
+
+        assert!(diagram.set(4, 0, 'x').is_err());
+        assert!(diagram.set(0, 0, 'y').is_err());
+
+        // Moving a mark passes through an invalid grid, which is allowed
+        diagram.set(0, 1, ' ').unwrap();
+        diagram.set(0, 0, 'x').unwrap();
+        assert_eq!(diagram.get(0, 0), Some('x'));
+        assert_eq!(diagram.get(0, 1), Some(' '));
+    }
+
+    #[test]
+    fn mark_positions() {
+        let diagram = nested_grid();
+        assert_eq!(diagram.x_positions(), vec![(0, 1), (1, 0), (2, 2), (3, 3)]);
+        assert_eq!(diagram.o_positions(), vec![(0, 2), (1, 1), (2, 3), (3, 0)]);
+        assert_eq!(diagram.marks().count(), 8);
+        assert_eq!(diagram.marks().next(), Some(((0, 1), 'x')));
+    }
+
+    #[test]
+    fn generate_knot_reports_errors() {
+        let unknot = DiagramBuilder::new(2)
+            .set_x(0, 0)
+            .set_o(0, 1)
+            .set_o(1, 0)
+            .set_x(1, 1)
+            .build()
+            .unwrap();
+        assert!(unknot.generate_knot().is_ok());
+
+        let empty = Diagram::from_data(vec![]).unwrap();
+        assert!(empty.generate_knot().is_err());
+
+        let mut missing_mark = unknot.clone();
+        missing_mark.set(0, 0, ' ').unwrap();
+        assert!(missing_mark.generate_knot().is_err());
+
+        let hopf_link = Tangle::N(2).numerator_closure().unwrap();
+        assert_eq!(hopf_link.components().len(), 2);
+        assert!(hopf_link.generate_knot().is_err());
+    }
+
+    #[test]
+    fn builder_matches_from_data() {
+        let built = DiagramBuilder::new(4)
+            .set_x(0, 1)
+            .set_o(0, 2)
+            .set_x(1, 0)
+            .set_o(1, 1)
+            .set_x(2, 2)
+            .set_o(2, 3)
+            .set_o(3, 0)
+            .set_x(3, 3)
+            .build()
+            .unwrap();
+        assert_eq!(built, nested_grid());
+
+        // Out-of-range marks and incomplete grids are both reported by `build`
+        assert!(DiagramBuilder::new(2).set_x(2, 0).build().is_err());
+        assert!(DiagramBuilder::new(2).set_x(0, 0).build().is_err());
+    }
+
+    #[test]
+    fn generate_options_scale_the_path() {
+        let diagram = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let coarse = GenerateOptions {
+            refine_length: 100.0,
+            ..GenerateOptions::default()
+        };
+        let scaled = GenerateOptions {
+            scale: 2.0,
+            lift_amount: 0.5,
+            ..coarse
+        };
+
+        // Without any subdivision, both paths visit the same corners and crossings
+        let path = diagram.generate_path_with(&coarse).unwrap();
+        let scaled_path = diagram.generate_path_with(&scaled).unwrap();
+        assert_eq!(
+            path.get_number_of_vertices(),
+            scaled_path.get_number_of_vertices()
+        );
+        for (a, b) in path.get_vertices().iter().zip(scaled_path.get_vertices()) {
+            assert!(approx_eq(a.x * 2.0, b.x, EPSILON));
+            assert!(approx_eq(a.y * 2.0, b.y, EPSILON));
+            assert!(approx_eq(a.z * 5.0, b.z, EPSILON));
+        }
+        assert!(path.get_vertices().iter().any(|v| v.z > 0.0));
+
+        // A shorter refine length subdivides the path further
+        let refined = diagram
+            .generate_path_with(&GenerateOptions::default())
+            .unwrap();
+        assert!(refined.get_number_of_vertices() > path.get_number_of_vertices());
+    }
+
+    #[test]
+    fn linking_numbers() {
+        let hopf_link = Tangle::N(2).numerator_closure().unwrap();
+        assert_eq!(hopf_link.linking_number().abs(), 1);
+
+        // The (2, 4) torus link winds around itself twice
+        let torus_link = Tangle::N(4).numerator_closure().unwrap();
+        assert_eq!(torus_link.components().len(), 2);
+        assert_eq!(torus_link.linking_number().abs(), 2);
+
+        // Mirroring the link negates its linking number
+        let mut mirrored = hopf_link.clone();
+        mirrored.mirror(Axis::Row);
+        assert_eq!(mirrored.linking_number(), -hopf_link.linking_number());
+
+        let trefoil = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        assert_eq!(trefoil.components().len(), 1);
+        assert_eq!(trefoil.linking_number(), 0);
+    }
+
+    #[test]
+    fn reflections_preserve_the_knot_type() {
+        let partial = Diagram::from_data(vec![vec!['x', ' ', 'o'], vec![' ', 'x', ' ']]).unwrap();
+        let mut transposed = partial.clone();
+        transposed.transpose();
+        assert_eq!(transposed.get_number_of_rows(), 3);
+        assert_eq!(transposed.get_number_of_columns(), 2);
+        assert_eq!(transposed.get(2, 0), Some('o'));
+
+        let mut mirrored = partial.clone();
+        mirrored.mirror(Axis::Column);
+        assert_eq!(mirrored.get_data()[0], vec!['o', ' ', 'x']);
+        mirrored.mirror(Axis::Column);
+        assert_eq!(mirrored, partial);
+
+        // The figure-eight knot is amphichiral, and the Alexander polynomial cannot tell the
+        // trefoil from its mirror image either
+        for name in ["trefoil", "figure_eight"].iter() {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            let mut reflected = diagram.clone();
+            reflected.mirror(Axis::Row);
+            reflected.transpose();
+            assert_eq!(
+                reflected.alexander_polynomial(),
+                diagram.alexander_polynomial()
+            );
+        }
+    }
+}
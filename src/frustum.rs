@@ -0,0 +1,89 @@
+use cgmath::{InnerSpace, Matrix, Matrix4, Vector3, Vector4};
+
+/// The six clipping planes of a camera's view volume, used to skip drawing objects that
+/// lie entirely off-screen.
+pub struct Frustum {
+    // The left, right, bottom, top, near, and far planes, each stored as `<a, b, c, d>` with
+    // a unit-length, inward-facing normal `<a, b, c>`, so that a point `p` lies inside the
+    // plane when `a * p.x + b * p.y + c * p.z + d >= 0`
+    planes: [Vector4<f32>; 6],
+}
+
+impl Frustum {
+    /// Extracts the clipping planes from the combined view-projection matrix `view_projection`
+    /// (the planes are expressed in world space).
+    ///
+    /// Reference: Gribb and Hartmann, "Fast Extraction of Viewing Frustum Planes from the
+    /// World-View-Projection Matrix"
+    pub fn new(view_projection: &Matrix4<f32>) -> Frustum {
+        let (r0, r1, r2, r3) = (
+            view_projection.row(0),
+            view_projection.row(1),
+            view_projection.row(2),
+            view_projection.row(3),
+        );
+        let normalize = |plane: Vector4<f32>| plane / plane.truncate().magnitude();
+
+        Frustum {
+            planes: [
+                normalize(r3 + r0),
+                normalize(r3 - r0),
+                normalize(r3 + r1),
+                normalize(r3 - r1),
+                normalize(r3 + r2),
+                normalize(r3 - r2),
+            ],
+        }
+    }
+
+    /// Returns `true` if any part of the sphere with the given `center` and `radius` could
+    /// be inside of the frustum. This is conservative: a sphere near one of the frustum's
+    /// corners may be reported as visible even though it is not.
+    pub fn intersects_sphere(&self, center: &Vector3<f32>, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(*center) + plane.w >= -radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::EPSILON;
+    use crate::utils::approx_eq;
+    use cgmath::{Deg, Point3};
+
+    /// Returns the frustum of a camera at `<0, 0, 10>` that looks at the origin with a 90
+    /// degree field of view, and near and far planes at `0.1` and `100`.
+    fn camera() -> Frustum {
+        let projection = cgmath::perspective(Deg(90.0), 1.0, 0.1, 100.0);
+        let view = Matrix4::look_at(
+            Point3::new(0.0, 0.0, 10.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vector3::unit_y(),
+        );
+        Frustum::new(&(projection * view))
+    }
+
+    #[test]
+    fn planes_are_normalized() {
+        for plane in camera().planes.iter() {
+            assert!(approx_eq(plane.truncate().magnitude(), 1.0, EPSILON));
+        }
+    }
+
+    #[test]
+    fn spheres_inside_and_outside() {
+        let frustum = camera();
+        assert!(frustum.intersects_sphere(&Vector3::new(0.0, 0.0, 0.0), 1.0));
+
+        // Behind the camera, beyond the far plane, and off to the side
+        assert!(!frustum.intersects_sphere(&Vector3::new(0.0, 0.0, 20.0), 1.0));
+        assert!(!frustum.intersects_sphere(&Vector3::new(0.0, 0.0, -200.0), 1.0));
+        assert!(!frustum.intersects_sphere(&Vector3::new(30.0, 0.0, 0.0), 1.0));
+
+        // Spheres that straddle a plane are visible
+        assert!(frustum.intersects_sphere(&Vector3::new(30.0, 0.0, 0.0), 20.0));
+        assert!(frustum.intersects_sphere(&Vector3::new(0.0, 0.0, 10.5), 1.0));
+    }
+}
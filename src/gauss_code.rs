@@ -0,0 +1,597 @@
+/// A single visit to a crossing while traversing a knot: the crossing's label, whether the
+/// knot passes over (`true`) or under (`false`) the crossing, and the crossing's sign.
+pub type Visit = (usize, bool, i32);
+
+/// The distance between adjacent rows (and columns) of the intermediate drawing, which leaves
+/// room for the small detours that are added around some of the crossings.
+const SPACING: i64 = 10;
+
+const NOT_PLANAR: &str = "Invalid Gauss code: the crossings cannot be drawn in the plane";
+const LAYOUT_FAILED: &str = "Failed to lay out the Gauss code as a grid diagram";
+
+/// The side of a crossing that one of its four strands leaves from.
+#[derive(Copy, Clone, PartialEq)]
+enum Port {
+    West,
+    South,
+    East,
+    North,
+}
+
+/// Builds the grid (in the format expected by `Diagram::from_data`) of a grid diagram whose
+/// projection realizes the given signed Gauss code, where each crossing is visited exactly
+/// twice (once over and once under) with the same sign. Nugatory crossings (those that can
+/// be undone by flipping part of the diagram over) are removed first.
+///
+/// The crossings are treated as the vertices of a 4-valent plane graph, which is drawn on a
+/// grid with the vertices on separate rows, in the order given by an st-numbering. Every
+/// vertical strand of a grid diagram passes over the horizontal strands that it meets, so a
+/// small detour is added around each crossing whose over-strand ends up horizontal.
+///
+/// Reference: Biedl and Kant, "A Better Heuristic for Orthogonal Graph Drawings"
+pub fn realize(visits: &[Visit]) -> Result<Vec<Vec<char>>, &'static str> {
+    let visits = remove_nugatory_crossings(visits.to_vec());
+    if visits.is_empty() {
+        return Ok(vec![vec!['x', 'o'], vec!['o', 'x']]);
+    }
+
+    // Relabel the crossings `0, 1, ..., n - 1` and find the visits along each crossing's
+    // over- and under-strand
+    let length = visits.len();
+    let mut labels: Vec<usize> = visits.iter().map(|visit| visit.0).collect();
+    labels.sort();
+    labels.dedup();
+    let n = labels.len();
+
+    let crossing: Vec<usize> = visits
+        .iter()
+        .map(|visit| labels.binary_search(&visit.0).unwrap())
+        .collect();
+    let mut over = vec![0; n];
+    let mut under = vec![0; n];
+    let mut signs = vec![0; n];
+    for (index, (_, is_over, sign)) in visits.iter().enumerate() {
+        if *is_over {
+            over[crossing[index]] = index;
+        } else {
+            under[crossing[index]] = index;
+        }
+        signs[crossing[index]] = *sign;
+    }
+
+    // Edge `i` of the graph runs from the crossing of visit `i` to the crossing of visit
+    // `i + 1`: its half-edges are `2 * i` (at its tail) and `2 * i + 1` (at its head)
+    let vertex_of = |half_edge: usize| {
+        if half_edge % 2 == 0 {
+            crossing[half_edge / 2]
+        } else {
+            crossing[(half_edge / 2 + 1) % length]
+        }
+    };
+    let outgoing = |index: usize| 2 * index;
+    let incoming = |index: usize| 2 * ((index + length - 1) % length) + 1;
+
+    // The half-edges around each crossing in counter-clockwise order, starting with the
+    // outgoing over-strand: the outgoing under-strand points to its left at a positive
+    // crossing and to its right at a negative crossing
+    let rotations: Vec<[usize; 4]> = (0..n)
+        .map(|c| {
+            let (o, u) = (over[c], under[c]);
+            if signs[c] > 0 {
+                [outgoing(o), outgoing(u), incoming(o), incoming(u)]
+            } else {
+                [outgoing(o), incoming(u), incoming(o), outgoing(u)]
+            }
+        })
+        .collect();
+
+    // By Euler's formula, the diagram is planar if and only if it has `n + 2` faces
+    let mut next_ccw = vec![0; 2 * length];
+    for rotation in rotations.iter() {
+        for k in 0..4 {
+            next_ccw[rotation[k]] = rotation[(k + 1) % 4];
+        }
+    }
+    let mut visited = vec![false; 2 * length];
+    let mut faces = 0;
+    for half_edge in 0..2 * length {
+        if !visited[half_edge] {
+            faces += 1;
+            let mut current = half_edge;
+            while !visited[current] {
+                visited[current] = true;
+                current = next_ccw[current ^ 1];
+            }
+        }
+    }
+    if faces != n + 2 {
+        return Err(NOT_PLANAR);
+    }
+
+    // Number the crossings so that every crossing other than the first (`s`) and the last
+    // (`t`) has a neighbor before it and a neighbor after it: the first edge joins `s` to `t`
+    let (s, t) = (crossing[0], crossing[1]);
+    let order = st_numbering(&crossing, n)?;
+    let mut rank = vec![0; n];
+    for (index, vertex) in order.iter().enumerate() {
+        rank[*vertex] = index;
+    }
+    let is_incoming =
+        |half_edge: usize| rank[vertex_of(half_edge ^ 1)] < rank[vertex_of(half_edge)];
+
+    // Sort the incoming and outgoing half-edges around each crossing from left to right: the
+    // first edge is drawn separately, around the right side of the rest of the diagram
+    let mut ins = vec![vec![]; n];
+    let mut outs = vec![vec![]; n];
+    for v in 0..n {
+        let rotation = rotations[v];
+        let start = if v == s {
+            rotation.iter().position(|half_edge| *half_edge == 0)
+        } else if v == t {
+            rotation.iter().position(|half_edge| *half_edge == 1)
+        } else {
+            (0..4).find(|k| !is_incoming(rotation[*k]) && is_incoming(rotation[(k + 3) % 4]))
+        }
+        .ok_or(LAYOUT_FAILED)?;
+        let walk: Vec<usize> = (0..4).map(|k| rotation[(start + k) % 4]).collect();
+
+        if v == s {
+            outs[v] = walk[1..].iter().rev().cloned().collect();
+        } else if v == t {
+            ins[v] = walk[1..].to_vec();
+        } else {
+            let count = walk
+                .iter()
+                .take_while(|half_edge| !is_incoming(**half_edge))
+                .count();
+            if walk[count..]
+                .iter()
+                .any(|half_edge| !is_incoming(*half_edge))
+            {
+                return Err(LAYOUT_FAILED);
+            }
+            outs[v] = walk[..count].iter().rev().cloned().collect();
+            ins[v] = walk[count..].to_vec();
+        }
+    }
+
+    // Place the crossings on consecutive rows from the bottom up, keeping a list of the edges
+    // that have been started but not finished (from left to right) along with their columns
+    let mut columns: Vec<usize> = vec![];
+    let mut column_of = vec![0; n];
+    let mut vertical = vec![[0; 2]; n];
+    let mut paths: Vec<Vec<(usize, i64)>> = vec![vec![]; length];
+    let mut pending: Vec<(usize, usize)> = vec![];
+
+    for (row, v) in order.iter().cloned().enumerate() {
+        let row = row as i64;
+        let in_ports = match ins[v].len() {
+            0 => vec![],
+            1 => vec![Port::South],
+            2 => vec![Port::West, Port::South],
+            _ => vec![Port::West, Port::South, Port::East],
+        };
+        let out_ports = match outs[v].len() {
+            0 => vec![],
+            1 => vec![Port::North],
+            2 => vec![Port::North, Port::East],
+            _ => vec![Port::West, Port::North, Port::East],
+        };
+
+        // The incoming edges must be adjacent (and in the same order) in the pending list
+        let edges: Vec<usize> = ins[v].iter().map(|half_edge| half_edge / 2).collect();
+        let start = match edges.first() {
+            Some(first) => pending
+                .iter()
+                .position(|(edge, _)| edge == first)
+                .ok_or(LAYOUT_FAILED)?,
+            None => 0,
+        };
+        if pending.len() < start + edges.len()
+            || pending[start..start + edges.len()]
+                .iter()
+                .map(|(edge, _)| *edge)
+                .ne(edges.iter().cloned())
+        {
+            return Err(LAYOUT_FAILED);
+        }
+
+        let column = if v == s {
+            columns.push(0);
+            0
+        } else {
+            let south = in_ports
+                .iter()
+                .position(|port| *port == Port::South)
+                .unwrap();
+            pending[start + south].1
+        };
+        column_of[v] = column;
+
+        for (edge, from) in pending.drain(start..start + edges.len()) {
+            paths[edge].push((from, row));
+            if from != column {
+                paths[edge].push((column, row));
+            }
+        }
+
+        let mut started = vec![];
+        for (half_edge, port) in outs[v].iter().zip(out_ports.iter()) {
+            let edge = half_edge / 2;
+            let to = match port {
+                Port::West | Port::East => {
+                    let id = columns.len();
+                    let index = columns.iter().position(|id| *id == column).unwrap();
+                    let index = if *port == Port::West {
+                        index
+                    } else {
+                        index + 1
+                    };
+                    columns.insert(index, id);
+                    id
+                }
+                _ => column,
+            };
+            paths[edge].push((column, row));
+            if to != column {
+                paths[edge].push((to, row));
+            }
+            started.push((edge, to));
+        }
+        pending.splice(start..start, started);
+
+        vertical[v] = [
+            if v == s {
+                0
+            } else {
+                ins[v][in_ports
+                    .iter()
+                    .position(|port| *port == Port::South)
+                    .unwrap()]
+            },
+            if v == t {
+                1
+            } else {
+                outs[v][out_ports
+                    .iter()
+                    .position(|port| *port == Port::North)
+                    .unwrap()]
+            },
+        ];
+    }
+    if !pending.is_empty() {
+        return Err(LAYOUT_FAILED);
+    }
+
+    // The first edge leaves `s` downwards and enters `t` from above
+    let far = columns.len();
+    columns.push(far);
+    let top = n as i64;
+    paths[0] = vec![
+        (column_of[s], 0),
+        (column_of[s], -1),
+        (far, -1),
+        (far, top),
+        (column_of[t], top),
+        (column_of[t], top - 1),
+    ];
+
+    // Join the edges into a single, closed rectilinear curve
+    let mut x = vec![0; columns.len()];
+    for (index, id) in columns.iter().enumerate() {
+        x[*id] = index as i64 * SPACING;
+    }
+    let mut points = vec![];
+    for (edge, path) in paths.iter().enumerate() {
+        let mut path = path.clone();
+        if rank[crossing[edge]] > rank[crossing[(edge + 1) % length]] {
+            path.reverse();
+        }
+        path.pop();
+        points.extend(path.iter().map(|(id, row)| (x[*id], row * SPACING)));
+    }
+
+    // At each crossing whose over-strand is horizontal, the vertical under-strand bumps out to
+    // the right and the over-strand loops back over it, which leaves a single crossing with the
+    // same sign, but with the over-strand vertical
+    for c in 0..n {
+        if vertical[c].contains(&outgoing(over[c])) {
+            continue;
+        }
+
+        let center = (x[column_of[c]], rank[c] as i64 * SPACING);
+        let (mut horizontal, mut vertical) = ((0, 0), (0, 0));
+        for (index, point) in points.iter().enumerate() {
+            if *point != center {
+                continue;
+            }
+            let previous = points[(index + points.len() - 1) % points.len()];
+            let next = points[(index + 1) % points.len()];
+            if previous.0 == next.0 {
+                vertical = (index, (next.1 - previous.1).signum());
+            } else {
+                horizontal = (index, (next.0 - previous.0).signum());
+            }
+        }
+
+        let place = |u: i64, w: i64| (center.0 + horizontal.1 * u, center.1 + vertical.1 * w);
+        let over_detour = vec![place(1, 0), place(1, -2), place(3, -2), place(3, 0)];
+        let under_detour = vec![place(0, -1), place(2, -1), place(2, 1), place(0, 1)];
+        if horizontal.0 > vertical.0 {
+            points.splice(horizontal.0..=horizontal.0, over_detour);
+            points.splice(vertical.0..=vertical.0, under_detour);
+        } else {
+            points.splice(vertical.0..=vertical.0, under_detour);
+            points.splice(horizontal.0..=horizontal.0, over_detour);
+        }
+    }
+
+    Ok(to_grid(points))
+}
+
+/// Repeatedly removes a nugatory crossing from `visits`, i.e. a crossing whose visits are
+/// separated by a loop that no other strand passes through. Flipping the loop over undoes
+/// the crossing and swaps the over- and under-strands of every crossing along the loop.
+fn remove_nugatory_crossings(mut visits: Vec<Visit>) -> Vec<Visit> {
+    loop {
+        let nugatory = (0..visits.len()).find_map(|first| {
+            let second = (first + 1..visits.len()).find(|j| visits[*j].0 == visits[first].0)?;
+            let inside = &visits[first + 1..second];
+            if inside
+                .iter()
+                .all(|visit| inside.iter().filter(|other| other.0 == visit.0).count() == 2)
+            {
+                Some((first, second))
+            } else {
+                None
+            }
+        });
+
+        match nugatory {
+            Some((first, second)) => {
+                for visit in visits[first + 1..second].iter_mut() {
+                    visit.1 = !visit.1;
+                }
+                visits.remove(second);
+                visits.remove(first);
+            }
+            None => return visits,
+        }
+    }
+}
+
+/// The state of the depth-first search used to compute an st-numbering.
+struct Search {
+    // The preorder number of each vertex (or `None` if it hasn't been visited yet)
+    number: Vec<Option<usize>>,
+
+    // The parent of each vertex in the search tree
+    parent: Vec<usize>,
+
+    // The vertex with the smallest preorder number that can be reached from each vertex by
+    // following tree edges down and then (at most) one back edge
+    low: Vec<usize>,
+
+    // The vertices in the order that they were visited
+    preorder: Vec<usize>,
+}
+
+impl Search {
+    fn visit(&mut self, neighbors: &[Vec<(usize, usize)>], v: usize, parent_edge: Option<usize>) {
+        self.number[v] = Some(self.preorder.len());
+        self.preorder.push(v);
+        self.low[v] = v;
+
+        for (edge, w) in neighbors[v].iter().cloned() {
+            if Some(edge) == parent_edge {
+                continue;
+            }
+            match self.number[w] {
+                None => {
+                    self.parent[w] = v;
+                    self.visit(neighbors, w, Some(edge));
+                    if self.number[self.low[w]] < self.number[self.low[v]] {
+                        self.low[v] = self.low[w];
+                    }
+                }
+                Some(number) => {
+                    if Some(number) < self.number[self.low[v]] {
+                        self.low[v] = w;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns an ordering of the `n` crossings of the knot diagram whose visits are given by
+/// `crossing`, which starts with `crossing[0]`, ends with `crossing[1]`, and in which every
+/// other crossing has a neighbor before it and a neighbor after it.
+///
+/// Reference: Tarjan, "Two Streamlined Depth-First Search Algorithms"
+fn st_numbering(crossing: &[usize], n: usize) -> Result<Vec<usize>, &'static str> {
+    let length = crossing.len();
+    let (s, t) = (crossing[0], crossing[1]);
+
+    let mut neighbors = vec![vec![]; n];
+    for edge in 0..length {
+        let (a, b) = (crossing[edge], crossing[(edge + 1) % length]);
+        neighbors[a].push((edge, b));
+        neighbors[b].push((edge, a));
+    }
+
+    // The search must leave `s` along the edge to `t` first
+    neighbors[s].sort_by_key(|(edge, _)| *edge != 0);
+
+    let mut search = Search {
+        number: vec![None; n],
+        parent: vec![0; n],
+        low: vec![0; n],
+        preorder: vec![],
+    };
+    search.visit(&neighbors, s, None);
+    if search.preorder.len() != n {
+        return Err(LAYOUT_FAILED);
+    }
+
+    // `is_plus[v]` records which side of `v` its next child should be inserted on
+    let mut order = vec![s, t];
+    let mut is_plus = vec![false; n];
+    for v in search.preorder.iter().skip(2).cloned() {
+        let parent = search.parent[v];
+        let index = order.iter().position(|u| *u == parent).unwrap();
+        if is_plus[search.low[v]] {
+            order.insert(index + 1, v);
+            is_plus[parent] = false;
+        } else {
+            order.insert(index, v);
+            is_plus[parent] = true;
+        }
+    }
+
+    let mut rank = vec![0; n];
+    for (index, vertex) in order.iter().enumerate() {
+        rank[*vertex] = index;
+    }
+    for v in 0..n {
+        if v != s && v != t {
+            let below = neighbors[v].iter().any(|(_, w)| rank[*w] < rank[v]);
+            let above = neighbors[v].iter().any(|(_, w)| rank[*w] > rank[v]);
+            if !below || !above {
+                return Err(LAYOUT_FAILED);
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// Converts a closed rectilinear curve (whose segments only meet at crossings, where they
+/// cross transversally) into a grid: each vertical segment gets its own column and each
+/// horizontal segment gets its own row.
+fn to_grid(mut points: Vec<(i64, i64)>) -> Vec<Vec<char>> {
+    // Merge consecutive segments that point in the same direction
+    loop {
+        let length = points.len();
+        let redundant = (0..length).find(|index| {
+            let previous = points[(index + length - 1) % length];
+            let next = points[(index + 1) % length];
+            let point = points[*index];
+            (previous.0 == point.0 && point.0 == next.0)
+                || (previous.1 == point.1 && point.1 == next.1)
+        });
+        match redundant {
+            Some(index) => {
+                points.remove(index);
+            }
+            None => break,
+        }
+    }
+
+    // Make sure that the curve starts with a vertical segment
+    if points[0].0 != points[1].0 {
+        points.rotate_left(1);
+    }
+
+    let length = points.len();
+    let size = length / 2;
+    let mut verticals: Vec<usize> = (0..length).step_by(2).collect();
+    let mut horizontals: Vec<usize> = (1..length).step_by(2).collect();
+    verticals.sort_by_key(|index| (points[*index].0, *index));
+    horizontals.sort_by_key(|index| (points[*index].1, *index));
+
+    // The rows of a grid diagram are numbered from the top down
+    let mut column = vec![0; length];
+    let mut row = vec![0; length];
+    for (rank, index) in verticals.iter().enumerate() {
+        column[*index] = rank;
+    }
+    for (rank, index) in horizontals.iter().enumerate() {
+        row[*index] = size - 1 - rank;
+    }
+
+    // Each vertical segment runs from an `x` to an `o`
+    let mut data = vec![vec![' '; size]; size];
+    for index in (0..length).step_by(2) {
+        data[row[(index + length - 1) % length]][column[index]] = 'x';
+        data[row[index + 1]][column[index]] = 'o';
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagram::Diagram;
+
+    const TREFOIL: [Visit; 6] = [
+        (1, true, 1),
+        (2, false, 1),
+        (3, true, 1),
+        (1, false, 1),
+        (2, true, 1),
+        (3, false, 1),
+    ];
+
+    #[test]
+    fn nugatory_crossings_are_removed() {
+        // A trefoil with a kink (crossing `4`)
+        let mut visits = TREFOIL.to_vec();
+        visits.insert(2, (4, true, -1));
+        visits.insert(3, (4, false, -1));
+        assert_eq!(remove_nugatory_crossings(visits), TREFOIL.to_vec());
+
+        // A trefoil inside of a loop (crossing `5`), which is flipped over
+        let mut visits = TREFOIL.to_vec();
+        visits.insert(0, (5, true, 1));
+        visits.push((5, false, 1));
+        let flipped: Vec<Visit> = TREFOIL
+            .iter()
+            .map(|(label, is_over, sign)| (*label, !is_over, *sign))
+            .collect();
+        assert_eq!(remove_nugatory_crossings(visits), flipped);
+
+        // Removing one nugatory crossing can make another one nugatory
+        let visits = vec![(1, true, 1), (2, true, -1), (2, false, -1), (1, false, 1)];
+        assert!(remove_nugatory_crossings(visits).is_empty());
+    }
+
+    #[test]
+    fn st_numbering_of_the_trefoil() {
+        let crossing: Vec<usize> = TREFOIL.iter().map(|visit| visit.0 - 1).collect();
+        let order = st_numbering(&crossing, 3).unwrap();
+        assert_eq!(order.first(), Some(&crossing[0]));
+        assert_eq!(order.last(), Some(&crossing[1]));
+    }
+
+    #[test]
+    fn realizes_the_gauss_code() {
+        let diagram = Diagram::from_data(realize(&TREFOIL).unwrap()).unwrap();
+        let knot = diagram.generate_knot().unwrap();
+
+        // Traverse the crossings of the knot's projection, as in `Knot::get_gauss_code`
+        let crossings = knot.find_crossings();
+        let mut visits = vec![];
+        for (index, crossing) in crossings.iter().enumerate() {
+            visits.push((crossing.over_segment, crossing.over_t, index, true));
+            visits.push((crossing.under_segment, crossing.under_t, index, false));
+        }
+        visits.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+
+        // The trefoil's code is the same up to relabeling and a cyclic shift
+        assert_eq!(visits.len(), 6);
+        for (index, visit) in visits.iter().enumerate() {
+            let next = visits[(index + 1) % 6];
+            assert_ne!(visit.3, next.3);
+            assert_eq!(visit.2, visits[(index + 3) % 6].2);
+            assert_eq!(crossings[visit.2].sign, 1);
+        }
+    }
+
+    #[test]
+    fn rejects_codes_that_are_not_planar() {
+        let visits = [(1, true, 1), (2, true, 1), (1, false, 1), (2, false, 1)];
+        assert_eq!(realize(&visits), Err(NOT_PLANAR));
+    }
+}
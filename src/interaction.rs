@@ -1,4 +1,8 @@
-use cgmath::{self, Vector2, Zero};
+use crate::constants;
+use crate::utils;
+use cgmath::{
+    self, InnerSpace, Matrix4, One, Quaternion, SquareMatrix, Vector2, Vector3, Vector4, Zero,
+};
 
 /// A simple struct for managing interaction state.
 pub struct InteractionState {
@@ -22,6 +26,24 @@ pub struct InteractionState {
 
     /// Whether or not the control key is pressed
     pub ctrl_pressed: bool,
+
+    /// The distance between the camera and the origin (adjusted with the scroll wheel)
+    pub camera_distance: f32,
+
+    /// The accumulated rotation of the scene about the origin (adjusted by dragging the mouse)
+    pub camera_rotation: Quaternion<f32>,
+
+    /// Whether or not the physics simulation is paused
+    pub paused: bool,
+
+    /// Whether or not a single step of the (paused) physics simulation has been requested
+    pub step_requested: bool,
+
+    /// Whether or not each knot is moved back to the origin after every step of the simulation
+    pub recenter: bool,
+
+    /// The index of the knot that Cromwell moves (entered with the number keys) are applied to
+    pub focused: usize,
 }
 
 impl InteractionState {
@@ -34,12 +56,155 @@ impl InteractionState {
             rmouse_pressed: false,
             shift_pressed: false,
             ctrl_pressed: false,
+            camera_distance: constants::DEFAULT_CAMERA_DISTANCE,
+            camera_rotation: Quaternion::one(),
+            paused: false,
+            step_requested: false,
+            recenter: false,
+            focused: 0,
         }
     }
 
-    /// Returns the amount that the cursor has moved since it was last pressed (used
-    /// during mouse-drag calculations).
-    pub fn get_mouse_delta(&self) -> Vector2<f32> {
-        self.cursor_curr - self.cursor_prev
+    /// Returns `true` if the physics simulation should be advanced this frame, i.e. it is
+    /// running or a single step was requested while paused. A step request is consumed by
+    /// this call, so that exactly one step is taken per request.
+    pub fn should_step(&mut self) -> bool {
+        if !self.paused {
+            return true;
+        }
+
+        let step = self.step_requested;
+        self.step_requested = false;
+        step
+    }
+
+    /// Moves the camera towards (positive `amount`) or away from (negative `amount`) the
+    /// origin, making sure that it never gets too close to (or passes through) the origin.
+    pub fn zoom(&mut self, amount: f32) {
+        self.camera_distance = (self.camera_distance - amount * constants::ZOOM_SENSITIVITY)
+            .max(constants::MIN_CAMERA_DISTANCE)
+            .min(constants::MAX_CAMERA_DISTANCE);
+    }
+}
+
+/// Projects a cursor position (in normalized screen coordinates, where `<0, 0>` is the
+/// top-left corner of the window) onto the unit "arcball" sphere centered in the window.
+/// Points outside of the sphere's silhouette are projected onto its rim.
+fn project_onto_arcball(cursor: Vector2<f32>) -> Vector3<f32> {
+    // Remap to `[-1..1]`, with the y-axis pointing up
+    let x = cursor.x * 2.0 - 1.0;
+    let y = 1.0 - cursor.y * 2.0;
+    let length_squared = x * x + y * y;
+
+    if length_squared <= 1.0 {
+        Vector3::new(x, y, (1.0 - length_squared).sqrt())
+    } else {
+        Vector3::new(x, y, 0.0).normalize()
+    }
+}
+
+/// Returns the rotation that carries the cursor position `from` to `to` (both in normalized
+/// screen coordinates) along the surface of an arcball centered in the window.
+pub fn get_arcball_rotation(from: Vector2<f32>, to: Vector2<f32>) -> Quaternion<f32> {
+    let a = project_onto_arcball(from);
+    let b = project_onto_arcball(to);
+
+    if utils::approx_eq_vec3(&a, &b, constants::EPSILON) {
+        return Quaternion::one();
+    }
+    Quaternion::from_arc(a, b, None)
+}
+
+/// Casts a ray from the camera through the cursor position `cursor` (in normalized screen
+/// coordinates, where `<0, 0>` is the top-left corner of the window), given the combined
+/// model-view-projection matrix `mvp`. The ray is returned as an `(origin, direction)` pair
+/// in model space, with a unit-length direction.
+pub fn get_cursor_ray(cursor: Vector2<f32>, mvp: &Matrix4<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let inverse = mvp.invert().unwrap_or(Matrix4::identity());
+
+    // Unproject the cursor at the near and far planes of the view frustum
+    let x = cursor.x * 2.0 - 1.0;
+    let y = 1.0 - cursor.y * 2.0;
+    let unproject = |z: f32| {
+        let point = inverse * Vector4::new(x, y, z, 1.0);
+        point.truncate() / point.w
+    };
+    let near = unproject(-1.0);
+    let far = unproject(1.0);
+
+    (near, (far - near).normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arcball_projection() {
+        let center = project_onto_arcball(Vector2::new(0.5, 0.5));
+        assert!(utils::approx_eq_vec3(
+            &center,
+            &Vector3::unit_z(),
+            constants::EPSILON
+        ));
+
+        // The top-left corner lies outside of the sphere, so it ends up on the rim
+        let corner = project_onto_arcball(Vector2::new(0.0, 0.0));
+        assert!(utils::approx_eq(
+            corner.magnitude(),
+            1.0,
+            constants::EPSILON
+        ));
+        assert!(utils::approx_eq(corner.z, 0.0, constants::EPSILON));
+        assert!(corner.x < 0.0 && corner.y > 0.0);
+    }
+
+    #[test]
+    fn arcball_rotation() {
+        let (from, to) = (Vector2::new(0.5, 0.5), Vector2::new(0.75, 0.5));
+        assert_eq!(get_arcball_rotation(from, from), Quaternion::one());
+
+        let rotated = get_arcball_rotation(from, to) * project_onto_arcball(from);
+        assert!(utils::approx_eq_vec3(
+            &rotated,
+            &project_onto_arcball(to),
+            constants::EPSILON
+        ));
+    }
+
+    #[test]
+    fn cursor_ray_through_the_center() {
+        let (origin, direction) = get_cursor_ray(Vector2::new(0.5, 0.5), &Matrix4::identity());
+        assert!(utils::approx_eq_vec3(
+            &origin,
+            &Vector3::new(0.0, 0.0, -1.0),
+            constants::EPSILON
+        ));
+        assert!(utils::approx_eq_vec3(
+            &direction,
+            &Vector3::unit_z(),
+            constants::EPSILON
+        ));
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut interaction = InteractionState::new();
+        interaction.zoom(1000.0);
+        assert_eq!(interaction.camera_distance, constants::MIN_CAMERA_DISTANCE);
+        interaction.zoom(-1000.0);
+        assert_eq!(interaction.camera_distance, constants::MAX_CAMERA_DISTANCE);
+    }
+
+    #[test]
+    fn single_steps_are_consumed() {
+        let mut interaction = InteractionState::new();
+        assert!(interaction.should_step());
+
+        interaction.paused = true;
+        assert!(!interaction.should_step());
+        interaction.step_requested = true;
+        assert!(interaction.should_step());
+        assert!(!interaction.should_step());
     }
 }
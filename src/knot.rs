@@ -1,8 +1,21 @@
+use crate::colormap;
 use crate::constants;
+use crate::curve::{tube_ring, PolylineExt};
+use crate::diagram::{self, Diagram, GenerateOptions};
+use crate::frustum::Frustum;
+use crate::knot_table;
 
-use cgmath::{InnerSpace, Vector3, Zero};
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4, Zero};
+use gl::types::GLenum;
 use graphics_utils::mesh::Mesh;
 use graphics_utils::polyline::{Polyline, Segment};
+use graphics_utils::program::Program;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 
 pub trait Notation {
     fn generate(&self) -> &str;
@@ -14,6 +27,26 @@ pub enum Crossing {
     Neither,
 }
 
+/// A crossing in the projection of a knot onto the XY-plane.
+#[derive(Clone, Copy, Debug)]
+pub struct ProjectedCrossing {
+    // The index of the rope segment that passes over the crossing (segment `i` runs from
+    // vertex `i` to vertex `i + 1`)
+    pub over_segment: usize,
+
+    // How far along the over-segment the crossing occurs, in the range `[0, 1)`
+    pub over_t: f32,
+
+    // The index of the rope segment that passes under the crossing
+    pub under_segment: usize,
+
+    // How far along the under-segment the crossing occurs, in the range `[0, 1)`
+    pub under_t: f32,
+
+    // The sign of the crossing: `+1` for a right-handed crossing and `-1` for a left-handed one
+    pub sign: i32,
+}
+
 struct Stick<'a> {
     start: &'a Bead,
     end: &'a Bead,
@@ -26,6 +59,9 @@ struct Bead {
     // The position of the bead in 3-space
     position: Vector3<f32>,
 
+    // The position of the bead at the previous time step (used for Verlet integration)
+    previous_position: Vector3<f32>,
+
     // The velocity of the bead
     velocity: Vector3<f32>,
 
@@ -41,7 +77,7 @@ struct Bead {
     // The cached index of this bead's right neighbor in the underlying polyline
     neighbor_r_index: usize,
 
-    // Whether or not this bead is active in the physics simulation
+    // Whether or not this bead is pinned in place (it still exerts forces on other beads)
     is_stuck: bool,
 }
 
@@ -54,6 +90,7 @@ impl Bead {
     ) -> Bead {
         Bead {
             position: *position,
+            previous_position: *position,
             velocity: Vector3::zero(),
             acceleration: Vector3::zero(),
             index,
@@ -74,47 +111,268 @@ impl Bead {
         self.neighbor_r_index = right;
     }
 
-    /// Apply forces to this bead and update its position, velocity, and acceleration, accordingly.
-    fn apply_forces(&mut self, force: &Vector3<f32>) {
-        // The (average?) length of each line segment ("stick"), prior to relaxation
-        let starting_length = 0.5;
-
-        // The maximum distance a bead can travel per time-step
-        let d_max = starting_length * 0.025;
+    /// Apply forces to this bead and update its position, velocity, and acceleration, accordingly,
+    /// advancing time by `dt` (where `1.0` is a single step at the reference rate). The bead travels
+    /// at most `d_max` units per unit of time.
+    fn apply_forces(&mut self, force: &Vector3<f32>, params: &PhysicsParams, d_max: f32, dt: f32) {
+        let d_max = d_max * dt;
 
-        // The closest any two sticks can be (note that this should be larger than `d_max`)
-        let d_close = starting_length * 0.25;
+        // Damping is applied per unit of time, rather than per step
+        let damping = params.damping.powf(dt);
 
-        // The mass of each node ("bead"): we leave this unchanged for now
-        let mass = 1.0;
+        // Accumulate acceleration
+        self.acceleration += force / params.mass;
 
-        // Velocity damping factor
-        let damping = 0.5;
+        let old = self.position;
 
-        // Integrate acceleration and velocity (with damping)
-        self.acceleration += force / mass;
-        self.velocity += self.acceleration;
-        self.velocity *= damping;
+        let displacement = match params.integrator {
+            Integrator::Euler => {
+                // Integrate acceleration and velocity (with damping)
+                self.velocity += self.acceleration * dt;
+                self.velocity *= damping;
+                self.velocity * dt
+            }
+            Integrator::Verlet => {
+                // The velocity is implicit in the difference between the current and previous
+                // positions (this assumes that `dt` doesn't change much from step to step)
+                (self.position - self.previous_position) * damping + self.acceleration * dt * dt
+            }
+        };
 
         // Zero out the acceleration for the next time step
         self.acceleration = Vector3::zero();
 
-        // Set new position
-        let old = self.position;
-
         // Each particle can travel (at most) `d_max` units each time step
-        let clamped = if self.velocity.magnitude() > d_max {
-            self.velocity.normalize() * d_max
+        let clamped = if displacement.magnitude() > d_max {
+            displacement.normalize() * d_max
         } else {
-            self.velocity
+            displacement
         };
 
         self.position += clamped;
+        self.previous_position = old;
+
+        if let Integrator::Verlet = params.integrator {
+            self.velocity = clamped / dt.max(constants::EPSILON);
+        }
 
         // TODO: prevent segments from intersecting
     }
 }
 
+/// The numerical scheme used to advance the physics simulation by a single time step.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Integrator {
+    // Semi-implicit Euler: velocity is integrated first, then position
+    Euler,
+
+    // Position Verlet: each bead's velocity is derived from its previous position
+    Verlet,
+}
+
+/// Determines how a knot is drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TubeStyle {
+    // A thin line loop through the vertices of the rope
+    Line,
+
+    // An extruded tube with the given radius and number of segments around its circumference
+    Tube { radius: f32, segments: usize },
+
+    // A flat ribbon of the given width, which twists along with the rope's (parallel
+    // transport) frame
+    Ribbon { width: f32 },
+
+    // A single point at each vertex of the rope
+    Points,
+}
+
+/// The surface properties used to shade a knot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    // The color that the knot's default (or per-vertex) coloring is multiplied by
+    pub base_color: Vector3<f32>,
+
+    // How rough the surface is, from `0.0` (perfectly smooth) to `1.0` (note that this isn't
+    // uploaded to the shaders until they have lighting that can use it)
+    pub roughness: f32,
+}
+
+impl Material {
+    /// Uploads this material to the `u_base_color` uniform of `program`, which should be bound.
+    pub fn apply(&self, program: &Program) {
+        program.uniform_3f("u_base_color", &self.base_color);
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            base_color: Vector3::new(1.0, 1.0, 1.0),
+            roughness: 0.5,
+        }
+    }
+}
+
+/// Determines how the radius of the tube drawn around a knot varies along the rope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RadiusProfile {
+    // The radius swells and shrinks smoothly with arc length (purely decorative)
+    Sinusoidal,
+
+    // The radius shrinks at sharp bends, so that the surface of the tube doesn't overlap itself
+    Curvature,
+}
+
+/// The constants that govern the mass-spring system used to relax a knot.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsParams {
+    // The (average?) length of each line segment ("stick"), prior to relaxation
+    pub starting_length: f32,
+
+    // The mass of each node ("bead")
+    pub mass: f32,
+
+    // Velocity damping factor
+    pub damping: f32,
+
+    // The stiffness of the (attractive) spring force between neighboring beads
+    pub spring_strength: f32,
+
+    // The exponent applied to the distance between neighboring beads (minus one)
+    pub spring_exponent: f32,
+
+    // The strength of the (repulsive) electrostatic force between non-neighboring beads
+    pub repulsion_strength: f32,
+
+    // The exponent applied to the distance between non-neighboring beads (minus two)
+    pub repulsion_exponent: f32,
+
+    // How much each bead wants to stay near its original position (`0.0` means that
+    // we ignore this force)
+    pub anchor_weight: f32,
+
+    // The integration scheme used to update each bead
+    pub integrator: Integrator,
+
+    // Whether or not segments are pulled back towards their rest length after every time step
+    pub preserve_length: bool,
+
+    // The number of times the length constraints are enforced per time step
+    pub constraint_iterations: usize,
+
+    // Whether or not the maximum distance each bead can travel per time step is scaled by the
+    // distance to its nearest non-neighboring bead (otherwise, it is the same for every bead)
+    pub adaptive_step: bool,
+
+    // The seed used to generate random perturbations (`None` means that the seed is chosen
+    // at random, so perturbations are not reproducible)
+    pub seed: Option<u64>,
+
+    // The starting temperature of the simulated annealing schedule, in units of Möbius energy
+    // (`0.0` means that we don't anneal)
+    pub temperature: f32,
+
+    // The factor by which the temperature is multiplied after every time step
+    pub cooling_rate: f32,
+}
+
+impl PhysicsParams {
+    /// Returns the maximum distance that a bead can travel per time step, given the distance
+    /// to its nearest non-neighboring bead (the "local feature size" of the rope). With
+    /// `adaptive_step`, beads with plenty of room around them move up to 4x further than
+    /// the default, while beads that are close to another strand move as little as 1/4 as
+    /// far, so that they don't pass through it.
+    fn get_step_limit(&self, feature_size: f32) -> f32 {
+        let d_max = self.starting_length * 0.025;
+        if self.adaptive_step {
+            d_max * (feature_size / self.starting_length).max(0.25).min(4.0)
+        } else {
+            d_max
+        }
+    }
+}
+
+impl Default for PhysicsParams {
+    fn default() -> Self {
+        PhysicsParams {
+            starting_length: 0.5,
+            mass: 1.0,
+            damping: 0.5,
+            spring_strength: 1.0,
+            spring_exponent: 1.0,
+            repulsion_strength: 0.5,
+            repulsion_exponent: 4.0,
+            anchor_weight: 0.0,
+            integrator: Integrator::Euler,
+            preserve_length: false,
+            constraint_iterations: 4,
+            adaptive_step: true,
+            seed: None,
+            temperature: 0.0,
+            cooling_rate: 0.95,
+        }
+    }
+}
+
+/// A summary of the state of the physics simulation, used to tell whether the relaxation
+/// is converging or oscillating.
+#[derive(Clone, Copy, Debug)]
+pub struct StepMetrics {
+    // The number of times the simulation has been advanced since it was (re)started
+    pub step: usize,
+
+    // The total length of the rope
+    pub total_length: f32,
+
+    // The Möbius energy of the rope (see `Knot::mobius_energy`)
+    pub mobius_energy: f32,
+
+    // The furthest distance that any bead traveled during the last time step
+    pub max_displacement: f32,
+}
+
+/// The invariants of a knot that can currently be computed, gathered by `Knot::invariants`.
+#[derive(Clone, Debug)]
+pub struct KnotInvariants {
+    // The number of crossings in the projection of the rope onto the XY-plane, which is an
+    // upper bound on the crossing number of the knot
+    pub crossings: usize,
+
+    // The determinant of the knot (see `Knot::determinant`)
+    pub determinant: i32,
+
+    // The writhe of the rope (see `Knot::writhe`), which depends on its current geometry
+    pub writhe: f32,
+
+    // The coefficients of the Alexander polynomial, starting with the constant term
+    pub alexander_polynomial: Vec<i32>,
+
+    // Whether or not the projection of the rope onto the XY-plane can be tricolored
+    pub tricolorable: bool,
+
+    // The hyperbolic volume of the knot's complement: this is not computed yet, so it is
+    // always `None`
+    pub volume: Option<f32>,
+}
+
+/// The serialized form of a knot (see `Knot::to_json`): vectors are stored as `[x, y, z]` arrays.
+#[derive(Serialize, Deserialize)]
+struct KnotSnapshot {
+    // The current (possibly relaxed) positions of the rope's vertices
+    rope: Vec<[f32; 3]>,
+
+    // Anchor (starting) positions
+    anchors: Vec<[f32; 3]>,
+
+    // Whether or not each bead is pinned in place
+    stuck: Vec<bool>,
+
+    // The constants used by the physics simulation
+    params: PhysicsParams,
+}
+
 /// A struct representing a knot, which is a polyline embedded in 3-dimensional space
 /// with a particular set of over- / under-crossings. In this program, a "knot" also
 /// refers to a dynamical model, where the underlying polyline is treated as a mass-spring
@@ -129,30 +387,156 @@ pub struct Knot {
     // All of the "beads" (i.e. points with a position, velocity, and acceleration) that make up this knot
     beads: Vec<Bead>,
 
-    // The GPU-side mesh used to render this knot
-    mesh: Mesh,
+    // The GPU-side mesh used to render this knot (created the first time it is drawn)
+    mesh: Option<Mesh>,
+
+    // The constants used by the physics simulation
+    params: PhysicsParams,
+
+    // The average length of the rope's segments, prior to relaxation
+    rest_length: f32,
+
+    // An (optional) color for each vertex of the rope
+    colors: Option<Vec<Vector3<f32>>>,
+
+    // The random number generator used to perturb the beads (seeded from `params.seed`)
+    rng: StdRng,
+
+    // How the radius of the tube varies along the rope when this knot is drawn
+    radius_profile: RadiusProfile,
+
+    // The surface properties used to shade this knot
+    material: Material,
+
+    // The number of times the simulation has been advanced since it was (re)started
+    step_count: usize,
+
+    // The furthest distance that any bead traveled during the last time step
+    max_displacement: f32,
+
+    // The current temperature of the simulated annealing schedule
+    temperature: f32,
+
+    // An (optional) CSV file that the metrics of each time step are written to
+    log: Option<BufWriter<File>>,
+
+    // The grid diagram (and options) that this knot was generated from, if any
+    source: Option<(Diagram, GenerateOptions)>,
 }
 
 impl Knot {
     pub fn new(rope: &Polyline, topology: Option<&Vec<Crossing>>) -> Knot {
-        let mut beads = vec![];
-        for (index, position) in rope.get_vertices().iter().enumerate() {
-            let (neighbor_l_index, neighbor_r_index) = rope.get_neighboring_indices_wrapped(index);
-
-            beads.push(Bead::new(
-                position,
-                index,
-                neighbor_l_index,
-                neighbor_r_index,
-            ));
-        }
+        let (beads, rest_length) = build_beads(rope);
 
         Knot {
             rope: rope.clone(),
             anchors: rope.clone(),
             beads,
-            mesh: Mesh::new(&vec![], None, None, None).unwrap(),
+            mesh: None,
+            params: PhysicsParams::default(),
+            rest_length,
+            colors: None,
+            rng: seeded_rng(None),
+            radius_profile: RadiusProfile::Sinusoidal,
+            material: Material::default(),
+            step_count: 0,
+            max_displacement: 0.0,
+            temperature: 0.0,
+            log: None,
+            source: None,
+        }
+    }
+
+    /// Returns the grid diagram that this knot was generated from, if any.
+    pub fn get_diagram(&self) -> Option<&Diagram> {
+        self.source.as_ref().map(|(diagram, _)| diagram)
+    }
+
+    /// Returns a mutable reference to the grid diagram that this knot was generated from, if
+    /// any. Changes to the diagram (e.g. Cromwell moves) only affect the knot once
+    /// `Knot::regenerate` is called.
+    pub fn get_diagram_mut(&mut self) -> Option<&mut Diagram> {
+        self.source.as_mut().map(|(diagram, _)| diagram)
+    }
+
+    /// Records the grid diagram (and the options) that this knot was generated from, so that
+    /// it can be rebuilt with `Knot::regenerate`.
+    pub fn set_diagram(&mut self, diagram: Diagram, opts: GenerateOptions) {
+        self.source = Some((diagram, opts));
+    }
+
+    /// Rebuilds the rope, anchors, and beads of this knot from the current state of its grid
+    /// diagram, restarting the simulation from scratch. The physics constants, material, and
+    /// radius profile are kept, while pinned beads and vertex colors are cleared (the number
+    /// of beads will generally change).
+    pub fn regenerate(&mut self) -> Result<(), &'static str> {
+        let rope = match &self.source {
+            Some((diagram, opts)) => diagram.generate_path_with(opts)?,
+            None => return Err("This knot was not generated from a grid diagram"),
+        };
+        let (beads, rest_length) = build_beads(&rope);
+
+        self.rope = rope.clone();
+        self.anchors = rope;
+        self.beads = beads;
+        self.rest_length = rest_length;
+        self.colors = None;
+        self.step_count = 0;
+        self.max_displacement = 0.0;
+        self.temperature = self.params.temperature;
+        Ok(())
+    }
+
+    /// Serializes the current state of this knot (its rope, anchors, pinned beads, and physics
+    /// constants) to a JSON string, so that a long relaxation can be resumed later with
+    /// `Knot::from_json`.
+    pub fn to_json(&self) -> String {
+        let to_array = |v: &Vector3<f32>| [v.x, v.y, v.z];
+
+        let snapshot = KnotSnapshot {
+            rope: self.rope.get_vertices().iter().map(to_array).collect(),
+            anchors: self.anchors.get_vertices().iter().map(to_array).collect(),
+            stuck: self.beads.iter().map(|bead| bead.is_stuck).collect(),
+            params: self.params,
+        };
+
+        // Serializing plain vectors and numbers can't fail
+        serde_json::to_string(&snapshot).unwrap()
+    }
+
+    /// Restores a knot from a JSON string produced by `Knot::to_json`. Bead velocities are
+    /// not saved, so the restored knot starts at rest.
+    pub fn from_json(s: &str) -> Result<Knot, serde_json::Error> {
+        let snapshot: KnotSnapshot = serde_json::from_str(s)?;
+
+        let to_polyline = |arrays: &Vec<[f32; 3]>| {
+            let mut polyline = Polyline::new();
+            for [x, y, z] in arrays.iter() {
+                polyline.push_vertex(&Vector3::new(*x, *y, *z));
+            }
+            polyline
+        };
+
+        let mut knot = Knot::new(&to_polyline(&snapshot.anchors), None);
+        knot.set_physics_params(snapshot.params);
+
+        let rope = to_polyline(&snapshot.rope);
+        for (index, bead) in knot.beads.iter_mut().enumerate() {
+            if let Some(position) = rope.get_vertices().get(index) {
+                bead.position = *position;
+                bead.previous_position = *position;
+            }
+            bead.is_stuck = snapshot.stuck.get(index).cloned().unwrap_or(false);
         }
+        knot.rope.set_vertices(&knot.gather_position_data());
+
+        Ok(knot)
+    }
+
+    /// Builds a knot from a closed curve stored in a Wavefront .obj file at `path` (see
+    /// `PolylineExt::from_obj`).
+    pub fn from_obj(path: &Path) -> io::Result<Knot> {
+        Ok(Knot::new(&Polyline::from_obj(path)?, None))
     }
 
     /// Returns an immutable reference to the polyline that formed this knot, prior
@@ -161,20 +545,104 @@ impl Knot {
         &self.rope
     }
 
+    /// Returns the constants used by the physics simulation.
+    pub fn get_physics_params(&self) -> &PhysicsParams {
+        &self.params
+    }
+
+    /// Sets the constants used by the physics simulation. This also re-seeds the random
+    /// number generator used by `perturb`.
+    pub fn set_physics_params(&mut self, params: PhysicsParams) {
+        self.params = params;
+        self.rng = seeded_rng(params.seed);
+        self.temperature = params.temperature;
+    }
+
+    /// Nudges every bead that isn't stuck by a random offset whose components lie in the
+    /// range `[-magnitude, magnitude]`, which can be used to kick the simulation out of a
+    /// configuration where it has stalled. Given the same seed (see `PhysicsParams::seed`),
+    /// the same sequence of perturbations is produced.
+    pub fn perturb(&mut self, magnitude: f32) {
+        if magnitude <= 0.0 {
+            return;
+        }
+
+        for bead in self.beads.iter_mut().filter(|bead| !bead.is_stuck) {
+            let offset = Vector3::new(
+                self.rng.gen_range(-magnitude, magnitude),
+                self.rng.gen_range(-magnitude, magnitude),
+                self.rng.gen_range(-magnitude, magnitude),
+            );
+
+            // Move the previous position as well, so that the Verlet integrator doesn't
+            // interpret the offset as a velocity
+            bead.position += offset;
+            bead.previous_position += offset;
+        }
+
+        // Update polyline positions for rendering
+        self.rope.set_vertices(&self.gather_position_data());
+    }
+
+    /// Returns the index of the bead closest to the camera whose distance from the ray
+    /// `<ray_origin, ray_direction>` (in model space) is less than the radius of the tube
+    /// drawn around the rope, along with the distance from `ray_origin` to that bead
+    /// (measured along the ray), or `None` if the ray misses every bead.
+    pub fn pick(
+        &self,
+        ray_origin: &Vector3<f32>,
+        ray_direction: &Vector3<f32>,
+    ) -> Option<(usize, f32)> {
+        let direction = ray_direction.normalize();
+        let threshold = constants::TUBE_RADIUS;
+
+        let mut closest = None;
+        let mut closest_t = std::f32::MAX;
+
+        for (index, bead) in self.beads.iter().enumerate() {
+            // Project the bead onto the ray, ignoring beads behind the ray's origin
+            let t = (bead.position - ray_origin).dot(direction);
+            if t < 0.0 {
+                continue;
+            }
+
+            let distance = (ray_origin + direction * t - bead.position).magnitude();
+            if distance < threshold && t < closest_t {
+                closest = Some((index, t));
+                closest_t = t;
+            }
+        }
+        closest
+    }
+
+    /// Returns `true` if the bead at `index` is pinned in place during relaxation.
+    pub fn is_stuck(&self, index: usize) -> bool {
+        self.beads[index].is_stuck
+    }
+
+    /// Pins (or un-pins) the bead at `index`, so that it is (or isn't) moved by `relax`.
+    pub fn set_stuck(&mut self, index: usize, stuck: bool) {
+        self.beads[index].is_stuck = stuck;
+    }
+
     /// Performs a pseudo-physical form of topological refinement, based on spring
-    /// physics.
-    pub fn relax(&mut self) {
-        // How much each bead wants to stay near its original position (`0.0` means that
-        // we ignore this force)
-        let anchor_weight = 0.0;
+    /// physics, advancing the simulation by `dt` (where `1.0` is a single step at the
+    /// reference rate of `constants::STEPS_PER_SECOND` steps per second).
+    pub fn relax(&mut self, dt: f32) {
+        let params = self.params;
+        let previous_positions = self.gather_position_data();
 
         // Calculate forces
         let mut forces = vec![];
+        let mut feature_sizes = vec![];
 
         for bead in self.beads.iter() {
             // Sum all of the forces acting on this particular bead
             let mut force = Vector3::zero();
 
+            // The distance to the closest non-neighboring bead
+            let mut feature_size = std::f32::MAX;
+
             // Iterate over all potential neighbors
             for other in self.beads.iter() {
                 // Don't accumulate forces on itself
@@ -191,21 +659,22 @@ impl Knot {
                             continue;
                         }
 
-                        let beta = 1.0;
-                        let H = 1.0;
+                        let beta = params.spring_exponent;
+                        let H = params.spring_strength;
                         force += direction * H * r.powf(1.0 + beta);
                     } else {
                         // This is NOT a neighboring bead: calculate the (repulsive) electrostatic force
                         let mut direction = bead.position - other.position; // Reversed direction
                         let r = direction.magnitude();
                         direction = direction.normalize();
+                        feature_size = feature_size.min(r);
 
                         if r.abs() < constants::EPSILON {
                             continue;
                         }
 
-                        let alpha = 4.0;
-                        let K = 0.5;
+                        let alpha = params.repulsion_exponent;
+                        let K = params.repulsion_strength;
                         force += direction * K * r.powf(-(2.0 + alpha));
                     }
                 }
@@ -213,21 +682,343 @@ impl Knot {
 
             // Apply anchor force
             // ...
-            //force += anchor_force * anchor_weight;
+            //force += anchor_force * params.anchor_weight;
 
             forces.push(force);
+            feature_sizes.push(feature_size);
         }
 
         // Because of the borrow checker, we can't use an inner-loop above: instead, we
-        // apply forces here
-        for (bead, force) in self.beads.iter_mut().zip(forces.iter()) {
-            bead.apply_forces(force);
+        // apply forces here (stuck beads still push and pull on others, but never move)
+        for ((bead, force), feature_size) in self
+            .beads
+            .iter_mut()
+            .zip(forces.iter())
+            .zip(feature_sizes.iter())
+        {
+            if !bead.is_stuck {
+                bead.apply_forces(force, &params, params.get_step_limit(*feature_size), dt);
+            }
+        }
+
+        if params.preserve_length {
+            for _ in 0..params.constraint_iterations {
+                self.enforce_length_constraints();
+            }
+        }
+
+        // Update polyline positions for rendering
+        self.rope.set_vertices(&self.gather_position_data());
+
+        if self.temperature > constants::EPSILON {
+            self.anneal();
+        }
+
+        self.step_count += 1;
+        self.max_displacement = self
+            .beads
+            .iter()
+            .zip(previous_positions.iter())
+            .map(|(bead, previous)| (bead.position - previous).magnitude())
+            .fold(0.0, f32::max);
+        self.write_log_entry();
+    }
+
+    /// Performs a single step of simulated annealing: every bead is perturbed by a random
+    /// offset that shrinks as the knot cools, and the perturbation is kept if it lowers the
+    /// Möbius energy of the rope or, with probability `exp(-ΔE / temperature)`, if it doesn't.
+    /// Occasionally accepting "uphill" moves lets the knot escape configurations where the
+    /// forces alone would leave it stuck. Finally, the temperature is lowered.
+    fn anneal(&mut self) {
+        let saved: Vec<(Vector3<f32>, Vector3<f32>)> = self
+            .beads
+            .iter()
+            .map(|bead| (bead.position, bead.previous_position))
+            .collect();
+        let energy = self.mobius_energy();
+
+        let magnitude = 0.02
+            * self.params.starting_length
+            * (self.temperature / self.params.temperature.max(constants::EPSILON));
+        self.perturb(magnitude);
+
+        let delta = self.mobius_energy() - energy;
+        let accept = delta <= 0.0 || self.rng.gen::<f32>() < (-delta / self.temperature).exp();
+        if !accept {
+            for (bead, (position, previous_position)) in self.beads.iter_mut().zip(saved) {
+                bead.position = position;
+                bead.previous_position = previous_position;
+            }
+            self.rope.set_vertices(&self.gather_position_data());
+        }
+
+        self.temperature *= self.params.cooling_rate;
+    }
+
+    /// Returns a summary of the current state of the physics simulation.
+    pub fn step_metrics(&self) -> StepMetrics {
+        StepMetrics {
+            step: self.step_count,
+            total_length: self.total_length(),
+            mobius_energy: self.mobius_energy(),
+            max_displacement: self.max_displacement,
+        }
+    }
+
+    /// Starts writing the metrics of every subsequent time step (see `Knot::step_metrics`)
+    /// to a CSV file at `path`, replacing any log that was already open. Note that this
+    /// computes the Möbius energy at every step, which is quadratic in the number of beads.
+    pub fn start_logging(&mut self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "step,total_length,mobius_energy,max_displacement")?;
+        self.log = Some(writer);
+        Ok(())
+    }
+
+    /// Stops writing the metrics of each time step to the log (if any).
+    pub fn stop_logging(&mut self) {
+        if let Some(mut writer) = self.log.take() {
+            if let Err(e) = writer.flush() {
+                println!("Failed to flush the relaxation log: {}", e);
+            }
+        }
+    }
+
+    /// Appends the metrics of the current time step to the log (if any). If the log can't be
+    /// written to, logging stops.
+    fn write_log_entry(&mut self) {
+        if self.log.is_none() {
+            return;
+        }
+
+        let metrics = self.step_metrics();
+        if let Some(writer) = self.log.as_mut() {
+            if let Err(e) = writeln!(
+                writer,
+                "{},{},{},{}",
+                metrics.step, metrics.total_length, metrics.mobius_energy, metrics.max_displacement
+            ) {
+                println!("Failed to write to the relaxation log: {}", e);
+                self.log = None;
+            }
+        }
+    }
+
+    /// Returns the total length of the rope.
+    fn total_length(&self) -> f32 {
+        let vertices = self.rope.get_vertices();
+        (0..vertices.len())
+            .map(|index| (vertices[(index + 1) % vertices.len()] - vertices[index]).magnitude())
+            .sum()
+    }
+
+    /// Moves the endpoints of each segment symmetrically towards (or away from) one another,
+    /// so that the segment's length matches the rest length of the rope. This is the
+    /// constraint projection step of position-based dynamics. Stuck beads are never moved:
+    /// their neighbors absorb the entire correction instead.
+    fn enforce_length_constraints(&mut self) {
+        let n = self.beads.len();
+        for index in 0..n {
+            let next = (index + 1) % n;
+            let (stuck_a, stuck_b) = (self.beads[index].is_stuck, self.beads[next].is_stuck);
+            if index == next || (stuck_a && stuck_b) {
+                continue;
+            }
+
+            let delta = self.beads[next].position - self.beads[index].position;
+            let length = delta.magnitude();
+            if length < constants::EPSILON {
+                continue;
+            }
+            let correction = delta * ((length - self.rest_length) / length);
+
+            // Split the correction between the two endpoints
+            let (weight_a, weight_b) = match (stuck_a, stuck_b) {
+                (true, _) => (0.0, 1.0),
+                (_, true) => (1.0, 0.0),
+                _ => (0.5, 0.5),
+            };
+            self.beads[index].position += correction * weight_a;
+            self.beads[next].position -= correction * weight_b;
+        }
+    }
+
+    /// Returns the center of mass of this knot (i.e. the average of all of its beads' positions).
+    pub fn centroid(&self) -> Vector3<f32> {
+        if self.beads.is_empty() {
+            return Vector3::zero();
+        }
+
+        let sum = self
+            .beads
+            .iter()
+            .fold(Vector3::zero(), |sum, bead| sum + bead.position);
+        sum / self.beads.len() as f32
+    }
+
+    /// Returns a sphere (center and radius) that encloses every bead of this knot. The sphere
+    /// is centered at the knot's centroid, so it isn't necessarily the smallest such sphere.
+    pub fn bounding_sphere(&self) -> (Vector3<f32>, f32) {
+        let center = self.centroid();
+        let radius = self
+            .beads
+            .iter()
+            .map(|bead| (bead.position - center).magnitude())
+            .fold(0.0, f32::max);
+        (center, radius)
+    }
+
+    /// Returns `true` if any part of this knot (drawn with the model matrix `model`) could be
+    /// visible within `frustum`, based on its bounding sphere padded by the radius of the
+    /// tube drawn around the rope.
+    pub fn is_visible(&self, frustum: &Frustum, model: &Matrix4<f32>) -> bool {
+        let (center, radius) = self.bounding_sphere();
+        let center = (model * Vector4::new(center.x, center.y, center.z, 1.0)).truncate();
+
+        // Account for any scaling in the model matrix
+        let scale = model
+            .x
+            .truncate()
+            .magnitude()
+            .max(model.y.truncate().magnitude())
+            .max(model.z.truncate().magnitude());
+
+        frustum.intersects_sphere(&center, (radius + constants::TUBE_RADIUS) * scale)
+    }
+
+    /// Returns the (discrete) Möbius energy of this knot, a measure of how "tangled" the rope
+    /// is that is invariant under scaling. For every pair of distinct vertices `x` and `y`, we
+    /// accumulate `1 / |x - y|² - 1 / d(x, y)²`, where `d` is the (shorter) arc length between
+    /// the two vertices along the rope, weighted by the length of rope that each vertex
+    /// represents. A finely sampled, round circle has the smallest possible energy (`4`).
+    /// Note that this is quadratic in the number of vertices.
+    pub fn mobius_energy(&self) -> f32 {
+        let vertices = self.rope.get_vertices();
+        let n = vertices.len();
+        if n < 3 {
+            return 0.0;
+        }
+
+        // The length of each segment, and the arc length from the first vertex to each vertex
+        let lengths: Vec<f32> = (0..n)
+            .map(|index| (vertices[(index + 1) % n] - vertices[index]).magnitude())
+            .collect();
+        let total_length: f32 = lengths.iter().sum();
+        let mut arc_lengths = vec![0.0; n];
+        for index in 1..n {
+            arc_lengths[index] = arc_lengths[index - 1] + lengths[index - 1];
+        }
+
+        // Each vertex represents half of each of its adjacent segments
+        let weights: Vec<f32> = (0..n)
+            .map(|index| 0.5 * (lengths[(index + n - 1) % n] + lengths[index]))
+            .collect();
+
+        let mut energy = 0.0;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let distance = (vertices[j] - vertices[i]).magnitude2();
+                let arc = arc_lengths[j] - arc_lengths[i];
+                let arc = arc.min(total_length - arc);
+                if distance < constants::EPSILON || arc < constants::EPSILON {
+                    continue;
+                }
+
+                energy += (1.0 / distance - 1.0 / (arc * arc)) * weights[i] * weights[j];
+            }
+        }
+
+        // Each (unordered) pair contributes twice to the double integral
+        2.0 * energy
+    }
+
+    /// Returns the writhe of the rope, computed by evaluating the Gauss double integral over
+    /// every pair of non-adjacent segments. Each pair contributes the signed solid angle that
+    /// one segment subtends as seen from the other (Klenin and Langowski's exact formula for
+    /// straight segments). Unlike the writhe of a grid diagram, this is a real number that
+    /// depends on the geometry of the rope, and it approaches the writhe of the projected
+    /// diagram as the knot flattens out. Note that this is quadratic in the number of segments.
+    pub fn writhe(&self) -> f32 {
+        let vertices = self.rope.get_vertices();
+        let n = vertices.len();
+        if n < 4 {
+            return 0.0;
+        }
+
+        let mut writhe = 0.0;
+        for i in 0..n {
+            for j in (i + 2)..n {
+                // The first and last segments are adjacent, too
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                writhe += gauss_solid_angle(
+                    &vertices[i],
+                    &vertices[(i + 1) % n],
+                    &vertices[j],
+                    &vertices[(j + 1) % n],
+                );
+            }
+        }
+
+        // Each (unordered) pair contributes twice to the double integral, which is normalized
+        // by `4π`
+        writhe / (2.0 * std::f32::consts::PI)
+    }
+
+    /// Translates this knot so that its centroid lies at the origin. This counteracts the
+    /// slow drift that occurs during relaxation (particularly when anchor forces are off).
+    pub fn recenter(&mut self) {
+        let centroid = self.centroid();
+        for bead in self.beads.iter_mut() {
+            bead.position -= centroid;
+            bead.previous_position -= centroid;
         }
 
         // Update polyline positions for rendering
         self.rope.set_vertices(&self.gather_position_data());
     }
 
+    /// Rebuilds this knot from `target_count` beads, spaced evenly by arc length along the
+    /// current (relaxed) rope. As the knot relaxes, beads tend to bunch up in some regions and
+    /// spread out in others: calling this periodically keeps the segments even. The anchors
+    /// are resampled in the same way (so that `reset` still works), the beads start at rest,
+    /// and any pinned beads or per-vertex colors are cleared.
+    pub fn resample(&mut self, target_count: usize) {
+        if target_count < 3 {
+            println!("Cannot resample a knot to fewer than 3 beads: ignoring");
+            return;
+        }
+
+        let positions = resample_closed(&self.gather_position_data(), target_count);
+        let anchors = resample_closed(self.anchors.get_vertices(), target_count);
+
+        self.anchors = Polyline::new();
+        for position in anchors.iter() {
+            self.anchors.push_vertex(position);
+        }
+        self.rope = self.anchors.clone();
+        self.rope.set_vertices(&positions);
+
+        // Rebuild the beads (and their neighbor indices) in the same order as before, so that
+        // the rope is traversed in the same direction and the knot keeps its crossings
+        self.beads = positions
+            .iter()
+            .enumerate()
+            .map(|(index, position)| {
+                let (neighbor_l_index, neighbor_r_index) =
+                    self.rope.get_neighboring_indices_wrapped(index);
+                Bead::new(position, index, neighbor_l_index, neighbor_r_index)
+            })
+            .collect();
+
+        let total_length: f32 = (0..target_count)
+            .map(|index| (anchors[(index + 1) % target_count] - anchors[index]).magnitude())
+            .sum();
+        self.rest_length = total_length / target_count as f32;
+        self.colors = None;
+    }
+
     /// Resets the physics simulation.
     pub fn reset(&mut self) {
         // First, reset the polyline
@@ -240,48 +1031,1753 @@ impl Knot {
             .zip(self.anchors.get_vertices().iter())
         {
             bead.position = *position;
+            bead.previous_position = *position;
+            bead.velocity = Vector3::zero();
         }
+
+        self.step_count = 0;
+        self.max_displacement = 0.0;
+        self.temperature = self.params.temperature;
     }
 
-    /// Draws this knot. If `extrude` is set to `true`, then the knot will be drawn
-    /// as an extruded tube (i.e. with "thickness"). Otherwise, it will be drawn as
-    /// a thin line loop.
-    pub fn draw(&mut self, extrude: bool) {
+    /// Draws this knot with `program`, which should be bound. If `extrude` is set to `true`,
+    /// then the knot will be drawn as an extruded tube (i.e. with "thickness"). Otherwise,
+    /// it will be drawn as a thin line loop.
+    pub fn draw(&mut self, program: &Program, extrude: bool) {
         if extrude {
-            let vertices = self.rope.generate_tube(
-                0.5,
-                12,
-                Some(&|pct| (pct as f32 * std::f32::consts::PI).sin() * 0.5 + 0.5),
+            self.draw_styled(
+                program,
+                TubeStyle::Tube {
+                    radius: constants::TUBE_RADIUS,
+                    segments: constants::TUBE_SEGMENTS,
+                },
             );
-
-            self.mesh.set_positions(&vertices);
-            self.mesh.draw(gl::TRIANGLES);
-            self.mesh.draw(gl::POINTS);
         } else {
-            self.mesh.set_positions(self.rope.get_vertices());
-            self.mesh.draw(gl::LINE_LOOP);
-            self.mesh.draw(gl::POINTS);
+            self.draw_styled(program, TubeStyle::Line);
         }
     }
 
-    /// Aggregates all of the beads' position vectors.
-    fn gather_position_data(&self) -> Vec<Vector3<f32>> {
-        self.beads.iter().map(|bead| bead.position).collect()
+    /// Draws this knot in the given `style` with `program`, which should be bound. This knot's
+    /// material and coloring mode are uploaded to the program's uniforms first.
+    pub fn draw_styled(&mut self, program: &Program, style: TubeStyle) {
+        self.material.apply(program);
+        program.uniform_1f(
+            "u_use_vertex_colors",
+            if self.has_vertex_colors() { 1.0 } else { 0.0 },
+        );
+
+        let vertices = match style {
+            TubeStyle::Line | TubeStyle::Points => self.rope.get_vertices().clone(),
+            TubeStyle::Tube { radius, segments } => self.generate_tube(radius, segments),
+            TubeStyle::Ribbon { width } => self.generate_ribbon(width),
+        };
+
+        let colors = match style {
+            TubeStyle::Line | TubeStyle::Points => self.get_vertex_colors().cloned(),
+            _ => self.generate_tube_colors(style, vertices.len()),
+        };
+
+        let mesh = self
+            .mesh
+            .get_or_insert_with(|| Mesh::new(&vec![], None, None, None).unwrap());
+        mesh.set_positions(&vertices);
+        if let Some(colors) = colors {
+            mesh.set_colors(&colors);
+        }
+
+        for mode in get_draw_modes(style).iter() {
+            mesh.draw(*mode);
+        }
     }
 
-    pub fn find_crossings(&self) {
-        unimplemented!()
+    /// Returns the surface properties used to shade this knot.
+    pub fn get_material(&self) -> &Material {
+        &self.material
     }
 
-    pub fn get_number_of_crossings(&self) {
-        unimplemented!()
+    /// Sets the surface properties used to shade this knot.
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
     }
 
-    pub fn get_dowker_notation(&self) {
-        unimplemented!()
+    /// Returns `true` if per-vertex colors have been assigned to this knot (and they are
+    /// still valid for the current rope).
+    pub fn has_vertex_colors(&self) -> bool {
+        self.get_vertex_colors().is_some()
     }
 
-    pub fn get_conway_notation(&self) {
-        unimplemented!()
+    /// Returns the per-vertex colors of this knot, if they have been set and there is
+    /// exactly one color for each vertex of the rope.
+    fn get_vertex_colors(&self) -> Option<&Vec<Vector3<f32>>> {
+        match &self.colors {
+            Some(colors) if colors.len() == self.rope.get_number_of_vertices() => Some(colors),
+            _ => None,
+        }
+    }
+
+    /// Assigns a color to each vertex of the rope, which will be used (instead of the
+    /// shader's default coloring) when this knot is drawn. There should be exactly one color
+    /// per vertex.
+    pub fn set_vertex_colors(&mut self, colors: &Vec<Vector3<f32>>) {
+        if colors.len() != self.rope.get_number_of_vertices() {
+            println!(
+                "Expected {} vertex colors but received {}: ignoring",
+                self.rope.get_number_of_vertices(),
+                colors.len()
+            );
+            return;
+        }
+        self.colors = Some(colors.clone());
+    }
+
+    /// Removes any per-vertex colors that were assigned to this knot.
+    pub fn clear_vertex_colors(&mut self) {
+        self.colors = None;
+    }
+
+    /// Colors each vertex of the rope by its (normalized) arc length, measured from the
+    /// first vertex.
+    pub fn color_by_arc_length(&mut self) {
+        let vertices = self.rope.get_vertices();
+        let n = vertices.len();
+
+        let mut arc_lengths = vec![0.0; n];
+        for index in 1..n {
+            arc_lengths[index] =
+                arc_lengths[index - 1] + (vertices[index] - vertices[index - 1]).magnitude();
+        }
+        let total_length = arc_lengths.last().cloned().unwrap_or(0.0);
+
+        let colors = arc_lengths
+            .iter()
+            .map(|length| colormap::viridis(length / total_length.max(constants::EPSILON)))
+            .collect();
+        self.set_vertex_colors(&colors);
+    }
+
+    /// Colors each vertex of the rope by its curvature, relative to the most tightly bent
+    /// vertex.
+    pub fn color_by_curvature(&mut self) {
+        let curvatures: Vec<f32> = (0..self.rope.get_number_of_vertices())
+            .map(|index| self.curvature_at(index))
+            .collect();
+        let max_curvature = curvatures.iter().cloned().fold(0.0, f32::max);
+
+        let colors = curvatures
+            .iter()
+            .map(|curvature| colormap::viridis(curvature / max_curvature.max(constants::EPSILON)))
+            .collect();
+        self.set_vertex_colors(&colors);
+    }
+
+    /// Returns the (discrete) curvature of the rope at the vertex at `index` (see
+    /// `PolylineExt::curvature_at`).
+    pub fn curvature_at(&self, index: usize) -> f32 {
+        self.rope.curvature_at(index)
+    }
+
+    /// Returns the total curvature of the rope (see `PolylineExt::total_curvature`).
+    pub fn total_curvature(&self) -> f32 {
+        self.rope.total_curvature()
+    }
+
+    /// Sets how the radius of the tube varies along the rope when this knot is drawn.
+    pub fn set_radius_profile(&mut self, radius_profile: RadiusProfile) {
+        self.radius_profile = radius_profile;
+    }
+
+    /// Returns the factor by which the tube's radius is scaled at each vertex of the rope.
+    /// With `RadiusProfile::Curvature`, the effective radius never exceeds the local radius of
+    /// curvature (`1 / curvature`), which is the point at which the tube starts to overlap
+    /// itself on the inside of a bend.
+    pub fn get_radius_modifiers(&self, radius: f32) -> Vec<f32> {
+        let n = self.rope.get_number_of_vertices();
+        match self.radius_profile {
+            RadiusProfile::Sinusoidal => (0..n)
+                .map(|index| {
+                    let pct = index as f32 / n as f32;
+                    (pct * std::f32::consts::PI).sin() * 0.5 + 0.5
+                })
+                .collect(),
+            RadiusProfile::Curvature => (0..n)
+                .map(|index| {
+                    let curvature = self.curvature_at(index);
+                    if radius * curvature > 1.0 {
+                        1.0 / (radius * curvature)
+                    } else {
+                        1.0
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Extrudes the rope into a tube, returning a list of triangles (three vertices each). The
+    /// radius of the tube is scaled at each vertex by `Knot::get_radius_modifiers`.
+    fn generate_tube(&self, radius: f32, segments: usize) -> Vec<Vector3<f32>> {
+        let modifiers = self.get_radius_modifiers(radius);
+        self.rope.tube(|index| radius * modifiers[index], segments)
+    }
+
+    /// Sweeps a flat ribbon of the given `width` along the rope, returning a triangle strip
+    /// (see `PolylineExt::generate_ribbon`).
+    fn generate_ribbon(&self, width: f32) -> Vec<Vector3<f32>> {
+        self.rope.generate_ribbon(width)
+    }
+
+    /// Returns a color for each of the `count` vertices generated for `style` (i.e. by
+    /// `generate_tube` or `generate_ribbon`), or `None` if this knot has no per-vertex colors.
+    /// Each vertex of the tube or ribbon inherits the color of the vertex of the rope that it
+    /// was swept around.
+    fn generate_tube_colors(&self, style: TubeStyle, count: usize) -> Option<Vec<Vector3<f32>>> {
+        let colors = self.get_vertex_colors()?;
+        let n = colors.len();
+        if n == 0 {
+            return None;
+        }
+
+        Some(
+            (0..count)
+                .map(|index| match style {
+                    TubeStyle::Tube { segments, .. } => colors[tube_ring(index, segments) % n],
+                    TubeStyle::Ribbon { .. } => colors[(index / 2) % n],
+                    TubeStyle::Line | TubeStyle::Points => colors[index % n],
+                })
+                .collect(),
+        )
+    }
+
+    /// Extrudes the rope into a tube for export, returning each triangle along with its facet
+    /// normal. The winding order of each triangle is chosen so that its normal points away
+    /// from the rope.
+    fn generate_outward_triangles(&self) -> Vec<([Vector3<f32>; 3], Vector3<f32>)> {
+        let vertices = self.generate_tube(constants::TUBE_RADIUS, constants::TUBE_SEGMENTS);
+
+        vertices
+            .chunks(3)
+            .map(|triangle| {
+                let (a, mut b, mut c) = (triangle[0], triangle[1], triangle[2]);
+                let mut normal = triangle_normal(triangle);
+
+                // The closest point on the rope to this triangle lies "inside" of the tube
+                let centroid = (a + b + c) / 3.0;
+                let closest = self
+                    .rope
+                    .get_vertices()
+                    .iter()
+                    .min_by(|p, q| {
+                        (centroid - **p)
+                            .magnitude2()
+                            .partial_cmp(&(centroid - **q).magnitude2())
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .cloned()
+                    .unwrap_or(centroid);
+
+                if normal.dot(centroid - closest) < 0.0 {
+                    std::mem::swap(&mut b, &mut c);
+                    normal = -normal;
+                }
+
+                ([a, b, c], normal)
+            })
+            .collect()
+    }
+
+    /// Writes the extruded tube of this knot to a Wavefront .obj file at `path`. Each
+    /// triangle of the tube is written as a separate face with a flat normal, wound so that
+    /// the normal points away from the rope.
+    pub fn export_obj(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let triangles = self.generate_outward_triangles();
+
+        writeln!(writer, "# Exported from knots")?;
+        for (triangle, _) in triangles.iter() {
+            for vertex in triangle.iter() {
+                writeln!(writer, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+            }
+        }
+
+        for (_, normal) in triangles.iter() {
+            writeln!(writer, "vn {} {} {}", normal.x, normal.y, normal.z)?;
+        }
+
+        // Indices in .obj files are 1-based
+        for index in 0..triangles.len() {
+            let (a, b, c) = (index * 3 + 1, index * 3 + 2, index * 3 + 3);
+            let n = index + 1;
+            writeln!(writer, "f {}//{} {}//{} {}//{}", a, n, b, n, c, n)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the extruded tube of this knot to a binary .stl file at `path`. The winding
+    /// order of each triangle is chosen so that its facet normal points away from the rope.
+    pub fn export_stl(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let triangles = self.generate_outward_triangles();
+
+        // An 80-byte header (which is ignored by most readers), followed by the number of triangles
+        let mut header = [0u8; 80];
+        let description = b"Exported from knots";
+        header[..description.len()].copy_from_slice(description);
+        writer.write_all(&header)?;
+        writer.write_all(&(triangles.len() as u32).to_le_bytes())?;
+
+        for ([a, b, c], normal) in triangles.iter() {
+            for vector in [*normal, *a, *b, *c].iter() {
+                for component in [vector.x, vector.y, vector.z].iter() {
+                    writer.write_all(&component.to_le_bytes())?;
+                }
+            }
+
+            // The "attribute byte count," which is unused
+            writer.write_all(&0u16.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the projection of the rope onto the XY-plane to an .svg file at `path`, scaled
+    /// to fit a `width` x `height` viewport (with a small margin). Wherever the projection
+    /// crosses itself, the stroke of the lower (under) strand is interrupted so that the
+    /// diagram reads correctly.
+    pub fn export_svg(&self, path: &Path, width: u32, height: u32) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let vertices = self.rope.get_vertices();
+
+        // Fit the projected vertices to the viewport, preserving the aspect ratio
+        let margin = 0.05 * width.min(height) as f32;
+        let (mut min, mut max) = (
+            Vector3::new(std::f32::MAX, std::f32::MAX, 0.0),
+            Vector3::new(std::f32::MIN, std::f32::MIN, 0.0),
+        );
+        for vertex in vertices.iter() {
+            min.x = min.x.min(vertex.x);
+            min.y = min.y.min(vertex.y);
+            max.x = max.x.max(vertex.x);
+            max.y = max.y.max(vertex.y);
+        }
+        let scale = ((width as f32 - 2.0 * margin) / (max.x - min.x).max(constants::EPSILON))
+            .min((height as f32 - 2.0 * margin) / (max.y - min.y).max(constants::EPSILON));
+
+        // Note that the y-axis points downwards in SVG
+        let project = |v: &Vector3<f32>| {
+            (
+                margin + (v.x - min.x) * scale,
+                height as f32 - margin - (v.y - min.y) * scale,
+            )
+        };
+
+        // Find all of the points (expressed as parameters along each segment) where a segment
+        // passes under another segment
+        let n = vertices.len();
+        let mut cuts = vec![vec![]; n];
+        for crossing in self.find_crossings().iter() {
+            cuts[crossing.under_segment].push(crossing.under_t);
+        }
+
+        // The width of the gap (in pixels) left on either side of an under-crossing
+        let gap = 6.0;
+
+        let (x, y) = project(&vertices[0]);
+        let mut commands = vec![format!("M {:.2} {:.2}", x, y)];
+        for i in 0..n {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            let (a_x, a_y) = project(&a);
+            let (b_x, b_y) = project(&b);
+            let length = ((b_x - a_x).powi(2) + (b_y - a_y).powi(2)).sqrt();
+
+            cuts[i].sort_by(|s, t| s.partial_cmp(t).unwrap());
+            for t in cuts[i].iter() {
+                let offset = if length > 0.0 { gap / length } else { 0.0 };
+                let (before, after) = ((t - offset).max(0.0), (t + offset).min(1.0));
+                commands.push(format!(
+                    "L {:.2} {:.2} M {:.2} {:.2}",
+                    a_x + (b_x - a_x) * before,
+                    a_y + (b_y - a_y) * before,
+                    a_x + (b_x - a_x) * after,
+                    a_y + (b_y - a_y) * after
+                ));
+            }
+            commands.push(format!("L {:.2} {:.2}", b_x, b_y));
+        }
+
+        writeln!(
+            writer,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            width, height, width, height
+        )?;
+        writeln!(
+            writer,
+            "  <path d=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"2\" stroke-linecap=\"round\"/>",
+            commands.join(" ")
+        )?;
+        writeln!(writer, "</svg>")?;
+
+        Ok(())
+    }
+
+    /// Aggregates all of the beads' position vectors.
+    fn gather_position_data(&self) -> Vec<Vector3<f32>> {
+        self.beads.iter().map(|bead| bead.position).collect()
+    }
+
+    /// Returns all of the crossings in the projection of the rope onto the XY-plane. At each
+    /// crossing, the strand with the larger z-coordinate passes over the other.
+    pub fn find_crossings(&self) -> Vec<ProjectedCrossing> {
+        let vertices = self.rope.get_vertices();
+        let n = vertices.len();
+        let mut crossings = vec![];
+
+        for i in 0..n {
+            for j in (i + 2)..n {
+                // The first and last segments are adjacent, too
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                let (a0, a1) = (vertices[i], vertices[(i + 1) % n]);
+                let (b0, b1) = (vertices[j], vertices[(j + 1) % n]);
+
+                if let Some((s, t)) = intersect_xy(&a0, &a1, &b0, &b1) {
+                    let z_a = a0.z + (a1.z - a0.z) * s;
+                    let z_b = b0.z + (b1.z - b0.z) * t;
+                    let ((over_segment, over_t), (under_segment, under_t)) = if z_a > z_b {
+                        ((i, s), (j, t))
+                    } else {
+                        ((j, t), (i, s))
+                    };
+
+                    // The crossing is right-handed if the under-strand points to the left of
+                    // the over-strand
+                    let over = vertices[(over_segment + 1) % n] - vertices[over_segment];
+                    let under = vertices[(under_segment + 1) % n] - vertices[under_segment];
+                    let sign = if over.x * under.y - over.y * under.x > 0.0 {
+                        1
+                    } else {
+                        -1
+                    };
+
+                    crossings.push(ProjectedCrossing {
+                        over_segment,
+                        over_t,
+                        under_segment,
+                        under_t,
+                        sign,
+                    });
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Returns the number of crossings in the projection of the rope onto the XY-plane.
+    pub fn get_number_of_crossings(&self) -> usize {
+        self.find_crossings().len()
+    }
+
+    /// Traverses the rope (starting at its first vertex), returning each of the `2n`
+    /// encounters with the `n` crossings in `crossings` as a tuple: the index of the crossing
+    /// and whether the rope passes over (`true`) or under (`false`) it.
+    fn traverse_crossings(&self, crossings: &Vec<ProjectedCrossing>) -> Vec<(usize, bool)> {
+        let mut encounters = vec![];
+        for (index, crossing) in crossings.iter().enumerate() {
+            encounters.push((crossing.over_segment, crossing.over_t, index, true));
+            encounters.push((crossing.under_segment, crossing.under_t, index, false));
+        }
+        encounters.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+
+        encounters
+            .iter()
+            .map(|(_, _, index, is_over)| (*index, *is_over))
+            .collect()
+    }
+
+    /// Returns the Gauss code of the projection of this knot onto the XY-plane. Crossings are
+    /// numbered `1..=n` in the order that they are first reached while traversing the rope,
+    /// and each encounter is recorded as the crossing's number: positive when the rope passes
+    /// over the crossing and negative when it passes under.
+    pub fn get_gauss_code(&self) -> Vec<i32> {
+        let crossings = self.find_crossings();
+        let encounters = self.traverse_crossings(&crossings);
+
+        let mut labels = vec![0; crossings.len()];
+        let mut next_label = 1;
+        let mut code = vec![];
+
+        for (index, is_over) in encounters.iter() {
+            if labels[*index] == 0 {
+                labels[*index] = next_label;
+                next_label += 1;
+            }
+            code.push(if *is_over {
+                labels[*index]
+            } else {
+                -labels[*index]
+            });
+        }
+        code
+    }
+
+    /// Cuts the projection of the rope onto the XY-plane into `n` arcs at the under-crossings
+    /// of the `n` crossings in `crossings`, returning the arc that passes over each crossing
+    /// and the pair of arcs that enter and leave it underneath.
+    fn label_arcs(&self, crossings: &Vec<ProjectedCrossing>) -> (Vec<usize>, Vec<(usize, usize)>) {
+        let encounters = self.traverse_crossings(crossings);
+        let n = crossings.len();
+
+        // The arc index increases whenever the rope passes under a crossing
+        let mut over_arcs = vec![0; n];
+        let mut under_arcs = vec![(0, 0); n];
+        let mut arc = 0;
+        for (index, is_over) in encounters.iter() {
+            if *is_over {
+                over_arcs[*index] = arc % n;
+            } else {
+                under_arcs[*index] = (arc % n, (arc + 1) % n);
+                arc += 1;
+            }
+        }
+        (over_arcs, under_arcs)
+    }
+
+    /// Returns the coloring (or crossing) matrix of the projection of this knot onto the
+    /// XY-plane. The projection is cut into `n` arcs at its `n` under-crossings: each row of
+    /// the matrix corresponds to a crossing and each column to an arc. A row contains `2` in
+    /// the column of the crossing's over-arc and `-1` in the columns of the two under-arcs
+    /// (entries are summed if the arcs coincide).
+    fn coloring_matrix(&self) -> Vec<Vec<i64>> {
+        let crossings = self.find_crossings();
+        let n = crossings.len();
+        let (over_arcs, under_arcs) = self.label_arcs(&crossings);
+
+        let mut matrix = vec![vec![0; n]; n];
+        for index in 0..n {
+            let (incoming, outgoing) = under_arcs[index];
+            matrix[index][over_arcs[index]] += 2;
+            matrix[index][incoming] -= 1;
+            matrix[index][outgoing] -= 1;
+        }
+        matrix
+    }
+
+    /// Returns the determinant of this knot, i.e. `|Δ(-1)|` where `Δ` is the Alexander
+    /// polynomial. It is computed as the absolute value of any first minor of the coloring
+    /// matrix of the projection onto the XY-plane. The unknot has determinant `1`, the
+    /// trefoil `3`, and the figure-eight knot `5`.
+    pub fn determinant(&self) -> i32 {
+        let matrix = self.coloring_matrix();
+        if matrix.len() <= 1 {
+            return 1;
+        }
+
+        // Delete the last row and column
+        let n = matrix.len() - 1;
+        let minor = matrix[..n].iter().map(|row| row[..n].to_vec()).collect();
+        integer_determinant(minor).abs() as i32
+    }
+
+    /// Returns the coefficients of the Alexander polynomial of this knot, starting with the
+    /// constant term and normalized as in `Diagram::alexander_polynomial`. It is computed as
+    /// any first minor of the Alexander matrix of the projection onto the XY-plane, whose rows
+    /// and columns (like those of the coloring matrix) correspond to crossings and arcs.
+    pub fn alexander_polynomial(&self) -> Vec<i32> {
+        let crossings = self.find_crossings();
+        let n = crossings.len();
+        if n <= 1 {
+            return vec![1];
+        }
+        let (over_arcs, under_arcs) = self.label_arcs(&crossings);
+
+        // Each entry is a polynomial of degree (at most) one, stored as `[constant, t]`: a row
+        // contains `1 - t` in the column of the over-arc, and `t` and `-1` in the columns of
+        // the two under-arcs (swapped for left-handed crossings)
+        let mut matrix = vec![vec![vec![0, 0]; n]; n];
+        for (index, crossing) in crossings.iter().enumerate() {
+            let (incoming, outgoing) = under_arcs[index];
+            let (t, minus_one) = if crossing.sign > 0 {
+                (incoming, outgoing)
+            } else {
+                (outgoing, incoming)
+            };
+            matrix[index][over_arcs[index]][0] += 1;
+            matrix[index][over_arcs[index]][1] -= 1;
+            matrix[index][t][1] += 1;
+            matrix[index][minus_one][0] -= 1;
+        }
+
+        // Delete the last row and column
+        let minor = matrix[..n - 1]
+            .iter()
+            .map(|row| row[..n - 1].to_vec())
+            .collect();
+        diagram::normalize_alexander_polynomial(&diagram::polynomial_determinant(minor))
+    }
+
+    /// Returns the name (in Rolfsen's notation, e.g. `"3_1"`) of the knot in the table of
+    /// prime knots with up to seven crossings whose invariants match those of this knot, or
+    /// `None` if there is no such knot. Knots are matched by their determinant and Alexander
+    /// polynomial, and only knots with no more crossings than the current projection are
+    /// considered. Mirror images are not distinguished, and a knot with more than seven
+    /// crossings that shares its invariants with a smaller knot will be misidentified.
+    pub fn identify(&self) -> Option<&'static str> {
+        knot_table::lookup(
+            self.get_number_of_crossings(),
+            self.determinant(),
+            &self.alexander_polynomial(),
+        )
+    }
+
+    /// Computes all of the available invariants of this knot at once. Invariants that depend
+    /// on a projection use the projection of the rope onto the XY-plane.
+    pub fn invariants(&self) -> KnotInvariants {
+        KnotInvariants {
+            crossings: self.get_number_of_crossings(),
+            determinant: self.determinant(),
+            writhe: self.writhe(),
+            alexander_polynomial: self.alexander_polynomial(),
+            tricolorable: self.is_tricolorable(),
+            volume: None,
+        }
+    }
+
+    /// Returns `true` if this knot appears to be the unknot. This is a heuristic, not a decision
+    /// procedure. A copy of the rope is relaxed for `steps` time steps (this knot is left
+    /// untouched), and the crossings of the relaxed projection onto the XY-plane are counted.
+    /// Any projection with fewer than three crossings is the unknot, so in that case the
+    /// answer is certain. Otherwise, we fall back to the invariants of the projection: a knot
+    /// whose determinant isn't `1` or whose Alexander polynomial isn't `1` is certainly
+    /// knotted. Checking both catches knots like the (3, 5) torus knot, whose determinant is
+    /// `1`, but a few non-trivial knots (such as the Kinoshita-Terasaka knot) have a trivial
+    /// Alexander polynomial and will still be misreported as the unknot.
+    pub fn is_unknot(&self, steps: usize) -> bool {
+        let mut relaxed = Knot::new(&self.rope, None);
+        relaxed.set_physics_params(self.params);
+        for _ in 0..steps {
+            relaxed.relax(1.0);
+        }
+
+        if relaxed.get_number_of_crossings() < 3 {
+            return true;
+        }
+        relaxed.determinant() == 1 && relaxed.alexander_polynomial() == vec![1]
+    }
+
+    /// Returns `true` if the projection of this knot onto the XY-plane can be tricolored, i.e.
+    /// its arcs can be colored with three colors (using more than one) such that the three
+    /// arcs that meet at each crossing are either all the same color or all different. Since
+    /// every constant coloring is valid, this is the case exactly when the coloring matrix has
+    /// rank less than `n - 1` over the integers modulo 3.
+    pub fn is_tricolorable(&self) -> bool {
+        let mut matrix: Vec<Vec<i64>> = self
+            .coloring_matrix()
+            .iter()
+            .map(|row| row.iter().map(|entry| entry.rem_euclid(3)).collect())
+            .collect();
+        let n = matrix.len();
+        if n == 0 {
+            return false;
+        }
+
+        // Gaussian elimination over the field with three elements
+        let mut rank = 0;
+        for col in 0..n {
+            let pivot = match (rank..n).find(|&row| matrix[row][col] != 0) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+            matrix.swap(rank, pivot);
+
+            // Every non-zero element is its own inverse modulo 3
+            let inverse = matrix[rank][col];
+            for j in 0..n {
+                matrix[rank][j] = (matrix[rank][j] * inverse) % 3;
+            }
+            for row in 0..n {
+                if row != rank && matrix[row][col] != 0 {
+                    let factor = matrix[row][col];
+                    for j in 0..n {
+                        matrix[row][j] = (matrix[row][j] - factor * matrix[rank][j]).rem_euclid(3);
+                    }
+                }
+            }
+            rank += 1;
+        }
+        rank < n - 1
+    }
+
+    /// Returns the planar diagram (PD) code of the projection of this knot onto the
+    /// XY-plane, as used by SnapPy and the KnotTheory package. The arcs between consecutive
+    /// crossing encounters are labeled `1..=2n` in the order that they are traversed. Each
+    /// crossing is described by its four incident arcs, listed counterclockwise starting from
+    /// the incoming under-strand.
+    pub fn get_pd_code(&self) -> Vec<[usize; 4]> {
+        let crossings = self.find_crossings();
+        let encounters = self.traverse_crossings(&crossings);
+        let total = encounters.len();
+
+        // The labels of the arcs entering and leaving each encounter
+        let incoming = |encounter: usize| if encounter == 0 { total } else { encounter };
+        let outgoing = |encounter: usize| encounter + 1;
+
+        let mut code = vec![];
+        for (under, (index, is_over)) in encounters.iter().enumerate() {
+            if *is_over {
+                continue;
+            }
+            let over = encounters
+                .iter()
+                .position(|encounter| *encounter == (*index, true))
+                .unwrap();
+
+            // Turning counterclockwise from the incoming under-strand, we reach the outgoing
+            // over-strand first at right-handed crossings
+            code.push(if crossings[*index].sign > 0 {
+                [
+                    incoming(under),
+                    outgoing(over),
+                    outgoing(under),
+                    incoming(over),
+                ]
+            } else {
+                [
+                    incoming(under),
+                    incoming(over),
+                    outgoing(under),
+                    outgoing(over),
+                ]
+            });
+        }
+        code
+    }
+
+    pub fn get_dowker_notation(&self) {
+        unimplemented!()
+    }
+
+    pub fn get_conway_notation(&self) {
+        unimplemented!()
+    }
+}
+
+/// Creates one bead per vertex of `rope`, returning the beads along with the average length
+/// of the rope's segments.
+fn build_beads(rope: &Polyline) -> (Vec<Bead>, f32) {
+    let mut beads = vec![];
+    for (index, position) in rope.get_vertices().iter().enumerate() {
+        let (neighbor_l_index, neighbor_r_index) = rope.get_neighboring_indices_wrapped(index);
+
+        beads.push(Bead::new(
+            position,
+            index,
+            neighbor_l_index,
+            neighbor_r_index,
+        ));
+    }
+
+    // Calculate the average segment length
+    let vertices = rope.get_vertices();
+    let total_length: f32 = (0..vertices.len())
+        .map(|index| (vertices[(index + 1) % vertices.len()] - vertices[index]).magnitude())
+        .sum();
+    let rest_length = total_length / (vertices.len().max(1) as f32);
+
+    (beads, rest_length)
+}
+
+/// Computes the determinant of a square matrix of integers, using the fraction-free Bareiss
+/// algorithm (so that every intermediate division is exact).
+fn integer_determinant(mut matrix: Vec<Vec<i64>>) -> i64 {
+    let n = matrix.len();
+    let mut sign = 1;
+    let mut previous_pivot = 1;
+
+    for k in 0..n.saturating_sub(1) {
+        // Find a non-zero pivot, swapping rows if necessary
+        if matrix[k][k] == 0 {
+            match (k + 1..n).find(|&row| matrix[row][k] != 0) {
+                Some(row) => {
+                    matrix.swap(k, row);
+                    sign = -sign;
+                }
+                None => return 0,
+            }
+        }
+
+        for i in k + 1..n {
+            for j in k + 1..n {
+                matrix[i][j] =
+                    (matrix[k][k] * matrix[i][j] - matrix[i][k] * matrix[k][j]) / previous_pivot;
+            }
+        }
+        previous_pivot = matrix[k][k];
+    }
+
+    match n {
+        0 => 1,
+        _ => sign * matrix[n - 1][n - 1],
+    }
+}
+
+/// Returns the signed solid angle subtended by the segment `<c, d>` as seen from every point
+/// along the segment `<a, b>` (i.e. `4π` times the contribution of this pair of segments to
+/// the Gauss linking integral), or `0.0` if the segments are degenerate or coplanar.
+fn gauss_solid_angle(
+    a: &Vector3<f32>,
+    b: &Vector3<f32>,
+    c: &Vector3<f32>,
+    d: &Vector3<f32>,
+) -> f32 {
+    let ac = c - a;
+    let ad = d - a;
+    let bc = c - b;
+    let bd = d - b;
+
+    // The normals of the faces of the tetrahedron spanned by the four points
+    let faces = [ac.cross(ad), ad.cross(bd), bd.cross(bc), bc.cross(ac)];
+    if faces
+        .iter()
+        .any(|normal| normal.magnitude() < constants::EPSILON * constants::EPSILON)
+    {
+        return 0.0;
+    }
+    let normals: Vec<Vector3<f32>> = faces.iter().map(|normal| normal.normalize()).collect();
+
+    let unsigned: f32 = (0..4)
+        .map(|index| {
+            normals[index]
+                .dot(normals[(index + 1) % 4])
+                .max(-1.0)
+                .min(1.0)
+                .asin()
+        })
+        .sum();
+
+    let sign = (d - c).cross(b - a).dot(ac);
+    if sign < 0.0 {
+        -unsigned
+    } else {
+        unsigned
+    }
+}
+
+/// Returns the primitive types used to draw a knot in the given `style`, in order.
+fn get_draw_modes(style: TubeStyle) -> Vec<GLenum> {
+    match style {
+        TubeStyle::Line => vec![gl::LINE_LOOP, gl::POINTS],
+        TubeStyle::Tube { .. } => vec![gl::TRIANGLES, gl::POINTS],
+        TubeStyle::Ribbon { .. } => vec![gl::TRIANGLE_STRIP],
+        TubeStyle::Points => vec![gl::POINTS],
+    }
+}
+
+/// Returns a random number generator seeded with `seed`, or with a random seed if `seed`
+/// is `None`.
+fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Resamples the closed polygon through `vertices` at `count` points, spaced evenly by arc
+/// length and starting at the first vertex.
+fn resample_closed(vertices: &[Vector3<f32>], count: usize) -> Vec<Vector3<f32>> {
+    let n = vertices.len();
+    if n < 2 {
+        return vertices.to_vec();
+    }
+
+    // The length of each segment, including the one that closes the loop
+    let lengths: Vec<f32> = (0..n)
+        .map(|index| (vertices[(index + 1) % n] - vertices[index]).magnitude())
+        .collect();
+    let total_length: f32 = lengths.iter().sum();
+    let spacing = total_length / count as f32;
+
+    let mut resampled = Vec::with_capacity(count);
+    let mut segment = 0;
+    let mut traveled = 0.0;
+    for index in 0..count {
+        let target = index as f32 * spacing;
+
+        // Advance to the segment that contains the target arc length
+        while segment < n - 1 && traveled + lengths[segment] < target {
+            traveled += lengths[segment];
+            segment += 1;
+        }
+
+        let t = if lengths[segment] > constants::EPSILON {
+            ((target - traveled) / lengths[segment]).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let start = vertices[segment];
+        let end = vertices[(segment + 1) % n];
+        resampled.push(start + (end - start) * t);
+    }
+
+    resampled
+}
+
+/// Returns the (unit) normal of the triangle formed by the first three vertices of `triangle`,
+/// assuming a counterclockwise winding order. Degenerate triangles have a zero normal.
+fn triangle_normal(triangle: &[Vector3<f32>]) -> Vector3<f32> {
+    let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+    if normal.magnitude() < constants::EPSILON {
+        return Vector3::zero();
+    }
+    normal.normalize()
+}
+
+/// Intersects the projections of the segments `<a0, a1>` and `<b0, b1>` onto the XY-plane,
+/// returning the parameters `(s, t)` of the intersection point along each segment, or `None`
+/// if the projections are parallel or don't intersect. Each parameter lies in the half-open
+/// range `[0, 1)`, so that an intersection at a shared endpoint of two consecutive segments
+/// is only reported once.
+fn intersect_xy(
+    a0: &Vector3<f32>,
+    a1: &Vector3<f32>,
+    b0: &Vector3<f32>,
+    b1: &Vector3<f32>,
+) -> Option<(f32, f32)> {
+    let r = a1 - a0;
+    let q = b1 - b0;
+    let denominator = r.x * q.y - r.y * q.x;
+
+    if denominator.abs() < constants::EPSILON * constants::EPSILON {
+        return None;
+    }
+
+    let d = b0 - a0;
+    let s = (d.x * q.y - d.y * q.x) / denominator;
+    let t = (d.x * r.y - d.y * r.x) / denominator;
+
+    let in_range = |u: f32| u >= -constants::EPSILON && u < 1.0 - constants::EPSILON;
+    if in_range(s) && in_range(t) {
+        Some((s.max(0.0), t.max(0.0)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagram::DiagramBuilder;
+    use std::path::PathBuf;
+
+    /// Returns a knot whose rope is a square with side length `4`, centered at the origin in
+    /// the xy-plane.
+    fn square() -> Knot {
+        let mut rope = Polyline::new();
+        for (x, y) in [(-2.0, -2.0), (2.0, -2.0), (2.0, 2.0), (-2.0, 2.0)].iter() {
+            rope.push_vertex(&Vector3::new(*x, *y, 0.0));
+        }
+        Knot::new(&rope, None)
+    }
+
+    /// Returns the knot generated from the bundled trefoil grid diagram.
+    fn trefoil() -> Knot {
+        let diagram = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        diagram.generate_knot().unwrap()
+    }
+
+    /// Returns the knot generated from the bundled figure-eight grid diagram.
+    fn figure_eight() -> Knot {
+        let diagram = Diagram::from_path(Path::new("diagrams/figure_eight.csv")).unwrap();
+        diagram.generate_knot().unwrap()
+    }
+
+    /// Returns a path in the system's temporary directory for a test to write `name` to.
+    fn temp_path(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join("knots_tests");
+        std::fs::create_dir_all(&directory).unwrap();
+        directory.join(name)
+    }
+
+    #[test]
+    fn default_material() {
+        let material = Material::default();
+        assert_eq!(material.base_color, Vector3::new(1.0, 1.0, 1.0));
+        assert_eq!(material.roughness, 0.5);
+        assert_eq!(*square().get_material(), material);
+    }
+
+    #[test]
+    fn pick_returns_the_nearest_bead() {
+        let knot = square();
+        let direction = Vector3::new(0.0, 0.0, -1.0);
+
+        let (index, t) = knot
+            .pick(&Vector3::new(2.1, 1.9, 10.0), &direction)
+            .unwrap();
+        assert_eq!(index, 2);
+        assert!((t - 10.0).abs() < 1e-4);
+
+        // Rays that pass between the beads (or point away from them) miss
+        assert!(knot
+            .pick(&Vector3::new(0.0, 0.0, 10.0), &direction)
+            .is_none());
+        assert!(knot
+            .pick(&Vector3::new(2.0, 2.0, 10.0), &-direction)
+            .is_none());
+    }
+
+    #[test]
+    fn export_obj_writes_one_face_per_triangle() {
+        let knot = trefoil();
+        let path = temp_path("trefoil.obj");
+        knot.export_obj(&path).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let count = |prefix: &str| {
+            contents
+                .lines()
+                .filter(|line| line.starts_with(prefix))
+                .count()
+        };
+        let (vertices, faces) = (count("v "), count("f "));
+        assert!(faces > 0);
+        assert_eq!(vertices, 3 * faces);
+        assert_eq!(count("vn "), faces);
+
+        // The last face refers to the last vertex and normal (indices are 1-based)
+        let last = format!(
+            "f {}//{3} {}//{3} {}//{3}",
+            vertices - 2,
+            vertices - 1,
+            vertices,
+            faces
+        );
+        assert_eq!(contents.lines().last(), Some(last.as_str()));
+
+        // Each face is wound to agree with its normal, which points away from the rope
+        let parse = |prefix: &str| -> Vec<Vector3<f32>> {
+            contents
+                .lines()
+                .filter(|line| line.starts_with(prefix))
+                .map(|line| {
+                    let c: Vec<f32> = line[prefix.len()..]
+                        .split_whitespace()
+                        .map(|token| token.parse().unwrap())
+                        .collect();
+                    Vector3::new(c[0], c[1], c[2])
+                })
+                .collect()
+        };
+        let (positions, normals) = (parse("v "), parse("vn "));
+        let rope = knot.get_rope().get_vertices();
+        for (triangle, normal) in positions.chunks(3).zip(normals.iter()) {
+            let winding = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+            assert!(winding.dot(*normal) >= 0.0);
+
+            let centroid = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+            let closest = rope
+                .iter()
+                .min_by(|p, q| {
+                    (centroid - **p)
+                        .magnitude2()
+                        .partial_cmp(&(centroid - **q).magnitude2())
+                        .unwrap()
+                })
+                .unwrap();
+            assert!(normal.dot(centroid - closest) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn export_stl_writes_every_triangle() {
+        let knot = trefoil();
+        let path = temp_path("trefoil.stl");
+        knot.export_stl(&path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let triangles = knot
+            .generate_tube(constants::TUBE_RADIUS, constants::TUBE_SEGMENTS)
+            .len()
+            / 3;
+        assert!(triangles > 0);
+        assert_eq!(&bytes[..19], b"Exported from knots");
+        assert_eq!(
+            u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize,
+            triangles
+        );
+
+        // Each triangle is a normal and three vertices (4 x 3 floats) and a 2-byte attribute
+        assert_eq!(bytes.len(), 84 + triangles * 50);
+    }
+
+    #[test]
+    fn export_svg_breaks_the_under_strands() {
+        let knot = trefoil();
+        let path = temp_path("trefoil.svg");
+        knot.export_svg(&path, 400, 300).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("<svg"));
+        assert!(contents.contains("viewBox=\"0 0 400 300\""));
+
+        // The path is restarted once after each of the three under-crossings
+        let commands = contents.split("d=\"").nth(1).unwrap();
+        let commands = &commands[..commands.find('"').unwrap()];
+        assert_eq!(commands.matches('M').count(), 1 + 3);
+
+        // Every point lies within the viewport
+        let coordinates: Vec<f32> = commands
+            .split_whitespace()
+            .filter_map(|token| token.parse().ok())
+            .collect();
+        for point in coordinates.chunks(2) {
+            assert!(point[0] >= 0.0 && point[0] <= 400.0);
+            assert!(point[1] >= 0.0 && point[1] <= 300.0);
+        }
+    }
+
+    #[test]
+    fn stuck_beads_stay_put() {
+        let mut knot = trefoil();
+        let pinned = knot.get_rope().get_vertices()[0];
+        knot.set_stuck(0, true);
+
+        // Pinned beads aren't moved by the length constraints either
+        knot.set_physics_params(PhysicsParams {
+            preserve_length: true,
+            ..PhysicsParams::default()
+        });
+        assert!(knot.is_stuck(0));
+        assert!(!knot.is_stuck(1));
+
+        for _ in 0..10 {
+            knot.relax(1.0);
+        }
+        assert_eq!(knot.get_rope().get_vertices()[0], pinned);
+        assert_ne!(
+            knot.get_rope().get_vertices()[1],
+            knot.anchors.get_vertices()[1]
+        );
+    }
+
+    #[test]
+    fn integrators() {
+        let step = |integrator: Integrator| {
+            let params = PhysicsParams {
+                integrator,
+                ..PhysicsParams::default()
+            };
+            let mut bead = Bead::new(&Vector3::zero(), 0, 0, 0);
+            bead.apply_forces(&Vector3::unit_x(), &params, 10.0, 1.0);
+            let first = bead.position.x;
+            bead.apply_forces(&Vector3::zero(), &params, 10.0, 1.0);
+            (first, bead.position.x)
+        };
+
+        // Euler damps the velocity before the first step, while Verlet only damps the
+        // velocity carried over from the previous step
+        assert_eq!(step(Integrator::Euler), (0.5, 0.75));
+        assert_eq!(step(Integrator::Verlet), (1.0, 1.5));
+
+        let mut knot = trefoil();
+        knot.set_physics_params(PhysicsParams {
+            integrator: Integrator::Verlet,
+            ..PhysicsParams::default()
+        });
+        for _ in 0..50 {
+            knot.relax(1.0);
+        }
+        assert!(knot
+            .get_rope()
+            .get_vertices()
+            .iter()
+            .all(|v| v.x.is_finite() && v.y.is_finite() && v.z.is_finite()));
+    }
+
+    #[test]
+    fn integrators_drift_alike() {
+        // The relative change in the length of the rope after a fixed number of steps
+        let drift = |integrator: Integrator, preserve_length: bool| {
+            let mut knot = trefoil();
+            knot.set_physics_params(PhysicsParams {
+                integrator,
+                preserve_length,
+                ..PhysicsParams::default()
+            });
+            let start = knot.total_length();
+            for _ in 0..100 {
+                knot.relax(0.5);
+            }
+            (knot.total_length() - start).abs() / start
+        };
+
+        // The springs pull the rope tight under either scheme, and they agree on how far
+        let (euler, verlet) = (
+            drift(Integrator::Euler, false),
+            drift(Integrator::Verlet, false),
+        );
+        assert!(euler > 0.1 && verlet > 0.1);
+        assert!(
+            (euler - verlet).abs() < 0.05 * euler,
+            "{} {}",
+            euler,
+            verlet
+        );
+
+        // With length constraints, neither scheme lets the rope stretch or shrink by much
+        let (euler, verlet) = (
+            drift(Integrator::Euler, true),
+            drift(Integrator::Verlet, true),
+        );
+        assert!(euler < 0.02 && verlet < 0.02, "{} {}", euler, verlet);
+        assert!((euler - verlet).abs() < 0.005, "{} {}", euler, verlet);
+    }
+
+    #[test]
+    fn length_constraints_restore_the_rest_length() {
+        let relaxed_length = |preserve_length: bool| {
+            let mut knot = trefoil();
+            knot.set_physics_params(PhysicsParams {
+                preserve_length,
+                constraint_iterations: 20,
+                ..PhysicsParams::default()
+            });
+            let rest_length = knot.rest_length * knot.beads.len() as f32;
+            for _ in 0..50 {
+                knot.relax(1.0);
+            }
+            (knot.total_length() - rest_length).abs() / rest_length
+        };
+
+        // Without the constraints, the length of the rope drifts as it relaxes
+        assert!(relaxed_length(true) < 0.01);
+        assert!(relaxed_length(true) < relaxed_length(false));
+    }
+
+    #[test]
+    fn recenter_moves_the_centroid_to_the_origin() {
+        assert_eq!(square().centroid(), Vector3::zero());
+
+        let mut rope = Polyline::new();
+        for (x, y) in [(1.0, 2.0), (3.0, 2.0), (3.0, 4.0), (1.0, 4.0)].iter() {
+            rope.push_vertex(&Vector3::new(*x, *y, 1.0));
+        }
+        let mut knot = Knot::new(&rope, None);
+        assert_eq!(knot.centroid(), Vector3::new(2.0, 3.0, 1.0));
+
+        knot.recenter();
+        assert_eq!(knot.centroid(), Vector3::zero());
+        assert_eq!(
+            knot.get_rope().get_vertices()[0],
+            Vector3::new(-1.0, -1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn bounding_sphere_encloses_every_bead() {
+        let (center, radius) = square().bounding_sphere();
+        assert_eq!(center, Vector3::zero());
+        assert!((radius - 8.0f32.sqrt()).abs() < constants::EPSILON);
+
+        let knot = trefoil();
+        let (center, radius) = knot.bounding_sphere();
+        let vertices = knot.get_rope().get_vertices();
+        assert!(vertices
+            .iter()
+            .all(|v| (v - center).magnitude() <= radius + constants::EPSILON));
+
+        // The sphere is tight: at least one bead lies on its surface
+        assert!(vertices
+            .iter()
+            .any(|v| ((v - center).magnitude() - radius).abs() < constants::EPSILON));
+    }
+
+    #[test]
+    fn mobius_energy_of_a_circle() {
+        let circle = |radius: f32| {
+            let mut rope = Polyline::new();
+            for index in 0..200 {
+                let angle = index as f32 / 200.0 * 2.0 * std::f32::consts::PI;
+                rope.push_vertex(&(Vector3::new(angle.cos(), angle.sin(), 0.0) * radius));
+            }
+            Knot::new(&rope, None)
+        };
+        let energy = circle(1.0).mobius_energy();
+        assert!((energy - 4.0).abs() < 0.1);
+
+        // The energy is invariant under scaling, and knotted ropes have more of it
+        assert!((circle(5.0).mobius_energy() - energy).abs() < 0.01);
+        assert!(trefoil().mobius_energy() > energy);
+    }
+
+    #[test]
+    fn vertex_colors() {
+        let mut knot = square();
+        assert!(!knot.has_vertex_colors());
+
+        // There must be exactly one color per vertex
+        knot.set_vertex_colors(&vec![Vector3::unit_x(); 3]);
+        assert!(!knot.has_vertex_colors());
+        knot.set_vertex_colors(&vec![Vector3::unit_x(); 4]);
+        assert!(knot.has_vertex_colors());
+        knot.clear_vertex_colors();
+        assert!(!knot.has_vertex_colors());
+
+        knot.color_by_arc_length();
+        let colors = knot.get_vertex_colors().unwrap();
+        assert_eq!(colors[0], colormap::viridis(0.0));
+        assert_eq!(colors[3], colormap::viridis(1.0));
+
+        // Colors no longer apply once the rope is resampled
+        knot.resample(8);
+        assert!(!knot.has_vertex_colors());
+    }
+
+    #[test]
+    fn tube_colors_follow_their_rings() {
+        let mut knot = square();
+        let style = TubeStyle::Tube {
+            radius: 0.1,
+            segments: 6,
+        };
+        let tube = knot.generate_tube(0.1, 6);
+        assert!(knot.generate_tube_colors(style, tube.len()).is_none());
+
+        let palette = vec![
+            Vector3::unit_x(),
+            Vector3::unit_y(),
+            Vector3::unit_z(),
+            Vector3::new(1.0, 1.0, 1.0),
+        ];
+        knot.set_vertex_colors(&palette);
+        let colors = knot.generate_tube_colors(style, tube.len()).unwrap();
+        assert_eq!(colors.len(), tube.len());
+
+        // Each vertex of the tube takes the color of the rope vertex at the center of its ring
+        // (which, for this square, is also the closest one)
+        let vertices = knot.get_rope().get_vertices();
+        for (index, vertex) in tube.iter().enumerate() {
+            let ring = palette.iter().position(|c| *c == colors[index]).unwrap();
+            let distance = (vertex - vertices[ring]).magnitude();
+            assert!(distance <= 0.1 + constants::EPSILON);
+            assert!(vertices
+                .iter()
+                .all(|other| (vertex - other).magnitude() >= distance - constants::EPSILON));
+        }
+
+        // Both sides of the ribbon share a color
+        let ribbon = knot.generate_ribbon(0.5);
+        let colors = knot
+            .generate_tube_colors(TubeStyle::Ribbon { width: 0.5 }, ribbon.len())
+            .unwrap();
+        assert_eq!(colors.len(), ribbon.len());
+        assert_eq!(colors[6], palette[3]);
+        assert_eq!(colors[7], palette[3]);
+        assert_eq!(colors[8], palette[0]);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut knot = trefoil();
+        knot.set_physics_params(PhysicsParams {
+            integrator: Integrator::Verlet,
+            seed: Some(3),
+            ..PhysicsParams::default()
+        });
+        knot.set_stuck(2, true);
+        for _ in 0..5 {
+            knot.relax(1.0);
+        }
+
+        let restored = Knot::from_json(&knot.to_json()).unwrap();
+        assert_eq!(
+            restored.get_rope().get_vertices(),
+            knot.get_rope().get_vertices()
+        );
+        assert_eq!(restored.anchors.get_vertices(), knot.anchors.get_vertices());
+        assert!(restored.is_stuck(2));
+        assert!(!restored.is_stuck(3));
+        assert_eq!(restored.get_physics_params().integrator, Integrator::Verlet);
+        assert_eq!(restored.get_physics_params().seed, Some(3));
+
+        // Missing physics constants fall back to their defaults
+        let partial = r#"{
+            "rope": [[0, 0, 0], [1, 0, 0], [0, 1, 0]],
+            "anchors": [[0, 0, 0], [1, 0, 0], [0, 1, 0]],
+            "stuck": [],
+            "params": {"mass": 2.0}
+        }"#;
+        let restored = Knot::from_json(partial).unwrap();
+        assert_eq!(restored.get_physics_params().mass, 2.0);
+        assert_eq!(restored.get_physics_params().damping, 0.5);
+
+        assert!(Knot::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn pd_code_of_the_trefoil() {
+        let code = trefoil().get_pd_code();
+        assert_eq!(code.len(), 3);
+
+        // Each of the 6 arcs meets two crossings (or the same crossing twice)
+        for label in 1..=6 {
+            let count = code
+                .iter()
+                .flat_map(|crossing| crossing.iter())
+                .filter(|arc| **arc == label)
+                .count();
+            assert_eq!(count, 2);
+        }
+
+        // The under-strand passes straight through each crossing
+        for [incoming, _, outgoing, _] in code.iter() {
+            assert_eq!(*outgoing, incoming % 6 + 1);
+        }
+
+        assert!(square().get_pd_code().is_empty());
+    }
+
+    #[test]
+    fn gauss_code_of_the_trefoil() {
+        let code = trefoil().get_gauss_code();
+        assert_eq!(code.len(), 6);
+
+        // Crossings are numbered in the order that they are first reached, and the trefoil is
+        // alternating
+        let mut next_label = 1;
+        for (index, encounter) in code.iter().enumerate() {
+            if encounter.abs() == next_label {
+                next_label += 1;
+            }
+            assert!(encounter.abs() < next_label);
+            assert_eq!(code[(index + 1) % 6].signum(), -encounter.signum());
+        }
+        for label in 1..=3 {
+            assert!(code.contains(&label) && code.contains(&-label));
+        }
+
+        assert!(square().get_gauss_code().is_empty());
+    }
+
+    #[test]
+    fn determinants() {
+        assert_eq!(square().determinant(), 1);
+        assert_eq!(trefoil().determinant(), 3);
+        assert_eq!(figure_eight().determinant(), 5);
+    }
+
+    #[test]
+    fn tricolorability() {
+        assert!(!square().is_tricolorable());
+        assert!(trefoil().is_tricolorable());
+        assert!(!figure_eight().is_tricolorable());
+    }
+
+    #[test]
+    fn resample_spaces_the_beads_evenly() {
+        let mut knot = square();
+        knot.resample(8);
+        assert_eq!(knot.beads.len(), 8);
+        assert_eq!(knot.get_rope().get_number_of_vertices(), 8);
+        assert!((knot.rest_length - 2.0).abs() < constants::EPSILON);
+
+        // Every segment (including the one that closes the loop) is the same length
+        let vertices = knot.get_rope().get_vertices();
+        for index in 0..8 {
+            let length = (vertices[(index + 1) % 8] - vertices[index]).magnitude();
+            assert!((length - 2.0).abs() < constants::EPSILON);
+        }
+        assert_eq!(vertices[1], Vector3::new(0.0, -2.0, 0.0));
+
+        // Too few beads are ignored
+        knot.resample(2);
+        assert_eq!(knot.beads.len(), 8);
+    }
+
+    #[test]
+    fn seeded_perturbations_are_reproducible() {
+        let run = |seed: u64| {
+            let mut knot = trefoil();
+            knot.set_physics_params(PhysicsParams {
+                seed: Some(seed),
+                ..PhysicsParams::default()
+            });
+            for _ in 0..5 {
+                knot.perturb(0.1);
+                knot.relax(1.0);
+            }
+            knot.get_rope().get_vertices().clone()
+        };
+
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn radius_shrinks_at_sharp_bends() {
+        let mut knot = square();
+
+        // Each corner turns a quarter of a turn over an average segment length of 4 units
+        let curvature = std::f32::consts::FRAC_PI_2 / 4.0;
+        assert!((knot.curvature_at(0) - curvature).abs() < constants::EPSILON);
+        assert!((knot.total_curvature() - 2.0 * std::f32::consts::PI).abs() < constants::EPSILON);
+
+        knot.set_radius_profile(RadiusProfile::Curvature);
+        assert_eq!(knot.get_radius_modifiers(1.0), vec![1.0; 4]);
+        for modifier in knot.get_radius_modifiers(4.0).iter() {
+            assert!((modifier * 4.0 - 1.0 / curvature).abs() < constants::EPSILON);
+        }
+
+        knot.set_radius_profile(RadiusProfile::Sinusoidal);
+        assert_eq!(knot.get_radius_modifiers(4.0)[0], 0.5);
+    }
+
+    #[test]
+    fn ribbons_have_the_requested_width() {
+        let knot = square();
+        let ribbon = knot.generate_ribbon(0.5);
+        assert_eq!(ribbon.len(), 2 * (4 + 1));
+
+        // The two sides of the ribbon straddle each vertex of the (planar) rope
+        for (index, vertex) in knot.get_rope().get_vertices().iter().enumerate() {
+            let (a, b) = (ribbon[2 * index], ribbon[2 * index + 1]);
+            assert!(((b - a).magnitude() - 0.5).abs() < constants::EPSILON);
+            assert!(((a + b) * 0.5 - vertex).magnitude() < constants::EPSILON);
+        }
+
+        assert_eq!(
+            get_draw_modes(TubeStyle::Ribbon { width: 0.5 }),
+            vec![gl::TRIANGLE_STRIP]
+        );
+        assert_eq!(get_draw_modes(TubeStyle::Points), vec![gl::POINTS]);
+    }
+
+    #[test]
+    fn writhe_approaches_the_writhe_of_the_diagram() {
+        assert_eq!(square().writhe(), 0.0);
+
+        // The crossings of generated knots are only slightly lifted off of the XY-plane
+        for name in ["trefoil", "figure_eight", "legendrian"].iter() {
+            let path = format!("diagrams/{}.csv", name);
+            let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+            let knot = diagram.generate_knot().unwrap();
+            assert!(
+                (knot.writhe() - diagram.writhe() as f32).abs() < 0.2,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn logging_writes_one_row_per_step() {
+        let mut knot = trefoil();
+        let path = temp_path("relaxation.csv");
+        knot.start_logging(&path).unwrap();
+        for _ in 0..3 {
+            knot.relax(1.0);
+        }
+        knot.stop_logging();
+        knot.relax(1.0);
+
+        let metrics = knot.step_metrics();
+        assert_eq!(metrics.step, 4);
+        assert!(metrics.max_displacement > 0.0);
+        assert!((metrics.total_length - knot.total_length()).abs() < constants::EPSILON);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1 + 3);
+        assert_eq!(lines[0], "step,total_length,mobius_energy,max_displacement");
+        assert!(lines[3].starts_with("3,"));
+
+        knot.reset();
+        assert_eq!(knot.step_metrics().step, 0);
+    }
+
+    #[test]
+    fn annealing_cools_the_knot() {
+        let mut knot = trefoil();
+        knot.set_physics_params(PhysicsParams {
+            temperature: 1.0,
+            cooling_rate: 0.5,
+            seed: Some(1),
+            ..PhysicsParams::default()
+        });
+        for _ in 0..3 {
+            knot.relax(1.0);
+        }
+        assert_eq!(knot.temperature, 0.125);
+        assert!(knot
+            .get_rope()
+            .get_vertices()
+            .iter()
+            .all(|v| v.x.is_finite() && v.y.is_finite() && v.z.is_finite()));
+
+        knot.reset();
+        assert_eq!(knot.temperature, 1.0);
+
+        // Without a starting temperature, the knot never anneals
+        let mut knot = trefoil();
+        knot.relax(1.0);
+        assert_eq!(knot.temperature, 0.0);
+    }
+
+    #[test]
+    fn annealing_finds_lower_energies() {
+        // Crumples the trefoil (identically, for a given seed) and relaxes it for a fixed
+        // number of steps, returning the final Möbius energy
+        let relaxed_energy = |seed: u64, temperature: f32| {
+            let mut knot = trefoil();
+            let params = PhysicsParams {
+                temperature,
+                cooling_rate: 0.95,
+                seed: Some(seed),
+                ..PhysicsParams::default()
+            };
+            knot.set_physics_params(params);
+            knot.perturb(0.5 * params.starting_length);
+            for _ in 0..20 {
+                knot.relax(1.0);
+            }
+            knot.mobius_energy()
+        };
+
+        for seed in 0..4 {
+            let (relaxed, annealed) = (relaxed_energy(seed, 0.0), relaxed_energy(seed, 0.5));
+            assert!(annealed < relaxed, "{} {} {}", seed, annealed, relaxed);
+        }
+    }
+
+    #[test]
+    fn from_obj_follows_the_polyline_elements() {
+        let path = temp_path("square.obj");
+        std::fs::write(
+            &path,
+            "# A square, traversed out of order\n\
+             v -2 -2 0\nv 2 2 0\nv 2 -2 0\nv -2 2 0\n\
+             l 1 3\nl 3 2\nl 2 4 1\n",
+        )
+        .unwrap();
+        let knot = Knot::from_obj(&path).unwrap();
+        assert_eq!(
+            knot.get_rope().get_vertices(),
+            square().get_rope().get_vertices()
+        );
+
+        assert_eq!(knot.beads.len(), 4);
+        assert!(Knot::from_obj(&temp_path("missing.obj")).is_err());
+    }
+
+    #[test]
+    fn step_limit_adapts_to_the_feature_size() {
+        let params = PhysicsParams::default();
+        let d_max = params.starting_length * 0.025;
+        assert_eq!(params.get_step_limit(params.starting_length), d_max);
+        assert_eq!(params.get_step_limit(100.0), d_max * 4.0);
+        assert_eq!(params.get_step_limit(0.0), d_max * 0.25);
+
+        let fixed = PhysicsParams {
+            adaptive_step: false,
+            ..params
+        };
+        assert_eq!(fixed.get_step_limit(100.0), d_max);
+        assert_eq!(fixed.get_step_limit(0.0), d_max);
+    }
+
+    #[test]
+    fn unknot_heuristic() {
+        assert!(square().is_unknot(0));
+        assert!(!figure_eight().is_unknot(0));
+
+        // The knot itself is left untouched
+        let knot = trefoil();
+        let before = knot.get_rope().get_vertices().clone();
+        assert!(!knot.is_unknot(10));
+        assert_eq!(knot.get_rope().get_vertices(), &before);
+
+        let unknot = DiagramBuilder::new(2)
+            .set_x(0, 0)
+            .set_o(0, 1)
+            .set_o(1, 0)
+            .set_x(1, 1)
+            .build()
+            .unwrap();
+        assert!(unknot.generate_knot().unwrap().is_unknot(10));
+
+        // The determinant of the (3, 5) torus knot is `1`, but its Alexander polynomial isn't
+        let torus_knot = Knot::new(&Polyline::torus_knot(3, 5, 300), None);
+        assert_eq!(torus_knot.determinant(), 1);
+        assert_ne!(torus_knot.alexander_polynomial(), vec![1]);
+        assert!(!torus_knot.is_unknot(0));
+    }
+
+    #[test]
+    fn time_step_scales_the_motion() {
+        let params = PhysicsParams::default();
+
+        // The distance a bead may travel scales with the time step
+        let mut bead = Bead::new(&Vector3::zero(), 0, 0, 0);
+        bead.apply_forces(&Vector3::new(100.0, 0.0, 0.0), &params, 0.1, 0.5);
+        assert!((bead.position.x - 0.05).abs() < constants::EPSILON);
+
+        // Below the clamp, a coasting bead travels a distance proportional to the time step
+        // (less a little damping), so halving the time step halves the distance
+        let coast = |integrator: Integrator, dt: f32| {
+            let params = PhysicsParams {
+                integrator,
+                ..PhysicsParams::default()
+            };
+            let mut bead = Bead::new(&Vector3::zero(), 0, 0, 0);
+            bead.velocity = Vector3::unit_x();
+            bead.previous_position = -Vector3::unit_x() * dt;
+            bead.apply_forces(&Vector3::zero(), &params, 10.0, dt);
+            bead.position.x
+        };
+        for integrator in [Integrator::Euler, Integrator::Verlet].iter() {
+            let (full, half) = (coast(*integrator, 0.02), coast(*integrator, 0.01));
+            assert!(full > 0.0 && full < 10.0 * 0.02);
+            assert!(
+                (full / half - 2.0).abs() < 0.02,
+                "{:?} {}",
+                integrator,
+                full / half
+            );
+        }
+
+        // A step of zero length doesn't move anything
+        let mut knot = trefoil();
+        let before = knot.get_rope().get_vertices().clone();
+        knot.relax(0.0);
+        assert_eq!(knot.get_rope().get_vertices(), &before);
+    }
+
+    #[test]
+    fn invariants_of_the_trefoil() {
+        let knot = trefoil();
+        let invariants = knot.invariants();
+        assert_eq!(invariants.crossings, 3);
+        assert_eq!(invariants.determinant, 3);
+        assert_eq!(invariants.alexander_polynomial, vec![1, -1, 1]);
+        assert!(invariants.tricolorable);
+        assert_eq!(invariants.writhe, knot.writhe());
+        assert!(invariants.volume.is_none());
+    }
+
+    #[test]
+    fn visibility_accounts_for_the_model_matrix() {
+        let projection = cgmath::perspective(cgmath::Deg(90.0), 1.0, 0.1, 100.0);
+        let view = Matrix4::look_at(
+            cgmath::Point3::new(0.0, 0.0, 10.0),
+            cgmath::Point3::new(0.0, 0.0, 0.0),
+            Vector3::unit_y(),
+        );
+        let frustum = Frustum::new(&(projection * view));
+
+        // The square's bounding sphere has a radius of `2√2`
+        let knot = square();
+        assert!(knot.is_visible(&frustum, &Matrix4::from_translation(Vector3::zero())));
+        let offscreen = Matrix4::from_translation(Vector3::new(15.0, 0.0, 0.0));
+        assert!(!knot.is_visible(&frustum, &offscreen));
+        assert!(knot.is_visible(&frustum, &(offscreen * Matrix4::from_scale(3.0))));
     }
 }
@@ -0,0 +1,76 @@
+/// The prime knots with up to seven crossings (and the unknot), named in Rolfsen's notation.
+/// Each entry holds the knot's name, crossing number, determinant, and Alexander polynomial
+/// (starting with the constant term and normalized so that its coefficients sum to `1`).
+const KNOTS: [(&str, usize, i32, &[i32]); 15] = [
+    ("0_1", 0, 1, &[1]),
+    ("3_1", 3, 3, &[1, -1, 1]),
+    ("4_1", 4, 5, &[-1, 3, -1]),
+    ("5_1", 5, 5, &[1, -1, 1, -1, 1]),
+    ("5_2", 5, 7, &[2, -3, 2]),
+    ("6_1", 6, 9, &[-2, 5, -2]),
+    ("6_2", 6, 11, &[-1, 3, -3, 3, -1]),
+    ("6_3", 6, 13, &[1, -3, 5, -3, 1]),
+    ("7_1", 7, 7, &[1, -1, 1, -1, 1, -1, 1]),
+    ("7_2", 7, 11, &[3, -5, 3]),
+    ("7_3", 7, 13, &[2, -3, 3, -3, 2]),
+    ("7_4", 7, 15, &[4, -7, 4]),
+    ("7_5", 7, 17, &[2, -4, 5, -4, 2]),
+    ("7_6", 7, 19, &[-1, 5, -7, 5, -1]),
+    ("7_7", 7, 21, &[1, -5, 9, -5, 1]),
+];
+
+/// Returns the name of the smallest knot in the table with the given `determinant` and
+/// `alexander_polynomial` whose crossing number is at most `max_crossings` (e.g. the number
+/// of crossings in some projection of the knot), or `None` if there is no such knot.
+pub fn lookup(
+    max_crossings: usize,
+    determinant: i32,
+    alexander_polynomial: &[i32],
+) -> Option<&'static str> {
+    KNOTS
+        .iter()
+        .filter(|(_, crossings, _, _)| *crossings <= max_crossings)
+        .find(|(_, _, d, polynomial)| *d == determinant && *polynomial == alexander_polynomial)
+        .map(|(name, _, _, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagram::Diagram;
+    use std::path::Path;
+
+    #[test]
+    fn every_polynomial_is_normalized() {
+        for (name, _, determinant, polynomial) in KNOTS.iter() {
+            let at_one: i32 = polynomial.iter().sum();
+            let at_minus_one: i32 = polynomial
+                .iter()
+                .enumerate()
+                .map(|(i, c)| if i % 2 == 0 { *c } else { -c })
+                .sum();
+            assert_eq!(at_one, 1, "{}", name);
+            assert_eq!(at_minus_one.abs(), *determinant, "{}", name);
+        }
+    }
+
+    #[test]
+    fn identifies_the_trefoil() {
+        let diagram = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let knot = diagram.generate_knot().unwrap();
+        assert_eq!(knot.identify(), Some("3_1"));
+    }
+
+    #[test]
+    fn identifies_the_figure_eight() {
+        let diagram = Diagram::from_path(Path::new("diagrams/figure_eight.csv")).unwrap();
+        let knot = diagram.generate_knot().unwrap();
+        assert_eq!(knot.identify(), Some("4_1"));
+    }
+
+    #[test]
+    fn lookup_respects_the_crossing_bound() {
+        assert_eq!(lookup(7, 5, &[1, -1, 1, -1, 1]), Some("5_1"));
+        assert_eq!(lookup(4, 5, &[1, -1, 1, -1, 1]), None);
+    }
+}
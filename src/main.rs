@@ -13,19 +13,28 @@
 
 extern crate gl;
 
+mod colormap;
 mod constants;
+mod curve;
 mod diagram;
+mod frustum;
+mod gauss_code;
 mod interaction;
 mod knot;
+mod knot_table;
 mod tangle;
 mod utils;
 
 use crate::diagram::{Axis, Cardinality, CromwellMove, Diagram, Direction};
-use crate::interaction::InteractionState;
-use cgmath::{EuclideanSpace, Matrix4, Point3, SquareMatrix, Vector3};
+use crate::frustum::Frustum;
+use crate::interaction::{get_arcball_rotation, get_cursor_ray, InteractionState};
+use crate::knot::Knot;
+use cgmath::{
+    EuclideanSpace, InnerSpace, Matrix4, One, Point3, Quaternion, SquareMatrix, Transform, Vector3,
+};
 use glutin::GlContext;
 use graphics_utils::program::Program;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Clears the default OpenGL framebuffer (color and depth)
 fn clear() {
@@ -33,6 +42,7 @@ fn clear() {
         gl::ClearColor(0.12, 0.1, 0.1, 1.0);
         gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
     }
+    utils::gl_check("glClear");
 }
 
 /// Sets the draw state (enables depth testing, etc.)
@@ -48,6 +58,112 @@ fn set_draw_state() {
         // Turn on back-face culling
         gl::Enable(gl::CULL_FACE);
     }
+    utils::gl_check("set_draw_state");
+}
+
+/// Returns the paths of all of the grid diagrams that should be loaded, given the program's
+/// command line arguments (the first of which is the name of the program itself). If no paths
+/// are specified, a default diagram is used.
+fn get_diagram_paths<I: IntoIterator<Item = String>>(args: I) -> Vec<PathBuf> {
+    let paths: Vec<PathBuf> = args.into_iter().skip(1).map(PathBuf::from).collect();
+
+    if paths.is_empty() {
+        vec![PathBuf::from("diagrams/legendrian.csv")]
+    } else {
+        paths
+    }
+}
+
+/// Returns a model matrix for each of `knots`, placing them side-by-side along the x-axis
+/// (centered at the origin) so that their bounding spheres are separated by `gap` units.
+fn layout_row(knots: &[Knot], gap: f32) -> Vec<Matrix4<f32>> {
+    let spheres: Vec<(Vector3<f32>, f32)> =
+        knots.iter().map(|knot| knot.bounding_sphere()).collect();
+    let total_width = spheres.iter().map(|(_, radius)| 2.0 * radius).sum::<f32>()
+        + gap * (spheres.len().max(1) - 1) as f32;
+
+    // Move each knot's bounding sphere onto the x-axis, just to the right of the last one
+    let mut x = -0.5 * total_width;
+    spheres
+        .iter()
+        .map(|(center, radius)| {
+            let translation = Vector3::new(x + radius, 0.0, 0.0) - center;
+            x += 2.0 * radius + gap;
+            Matrix4::from_translation(translation)
+        })
+        .collect()
+}
+
+/// Returns a view matrix for a camera that sits `distance` units from the origin (along the
+/// positive z-axis), looking at the origin, after the entire scene has been rotated by
+/// `rotation` (about the origin).
+fn get_view_matrix(distance: f32, rotation: Quaternion<f32>) -> Matrix4<f32> {
+    let look_at = Matrix4::look_at(
+        Point3::new(0.0, 0.0, distance),
+        Point3::origin(),
+        Vector3::unit_y(),
+    );
+    look_at * Matrix4::from(rotation)
+}
+
+/// Returns the distance from the origin at which a camera with a vertical field of view of
+/// `fov` (in radians) sees all of `knots` (each transformed by the corresponding model
+/// matrix in `models`).
+fn get_framing_distance(knots: &Vec<Knot>, models: &Vec<Matrix4<f32>>, fov: f32) -> f32 {
+    // Find a sphere centered at the origin that encloses every knot's bounding sphere
+    let radius = knots
+        .iter()
+        .zip(models.iter())
+        .map(|(knot, model)| {
+            let (center, radius) = knot.bounding_sphere();
+            model
+                .transform_point(Point3::from_vec(center))
+                .to_vec()
+                .magnitude()
+                + radius
+        })
+        .fold(0.0, f32::max);
+
+    // Leave some room for the knots to grow as they relax
+    (1.5 * radius / (fov * 0.5).sin())
+        .max(constants::MIN_CAMERA_DISTANCE)
+        .min(constants::MAX_CAMERA_DISTANCE)
+}
+
+/// Returns the Cromwell move that should be applied to `diagram` when `key` is pressed, or
+/// `None` if `key` isn't bound to a move (or the move it is bound to isn't available):
+///
+/// - `1` through `4`: translate the grid up, down, left, or right
+/// - `5` and `6`: commute the first two rows or columns
+/// - `7`: stabilize the `x` in the first row
+/// - `8`: destabilize the first 2x2 sub-grid that can be destabilized
+fn get_cromwell_move(key: glutin::VirtualKeyCode, diagram: &Diagram) -> Option<CromwellMove> {
+    match key {
+        glutin::VirtualKeyCode::Key1 => Some(CromwellMove::Translation(Direction::Up)),
+        glutin::VirtualKeyCode::Key2 => Some(CromwellMove::Translation(Direction::Down)),
+        glutin::VirtualKeyCode::Key3 => Some(CromwellMove::Translation(Direction::Left)),
+        glutin::VirtualKeyCode::Key4 => Some(CromwellMove::Translation(Direction::Right)),
+        glutin::VirtualKeyCode::Key5 => Some(CromwellMove::Commutation {
+            axis: Axis::Row,
+            start_index: 0,
+        }),
+        glutin::VirtualKeyCode::Key6 => Some(CromwellMove::Commutation {
+            axis: Axis::Column,
+            start_index: 0,
+        }),
+        glutin::VirtualKeyCode::Key7 => {
+            diagram.get_data()[0]
+                .iter()
+                .position(|&c| c == 'x')
+                .map(|j| CromwellMove::Stabilization {
+                    cardinality: Cardinality::NW,
+                    i: 0,
+                    j,
+                })
+        }
+        glutin::VirtualKeyCode::Key8 => diagram.find_destabilization(),
+        _ => None,
+    }
 }
 
 fn main() {
@@ -62,65 +178,57 @@ fn main() {
     unsafe { gl_window.make_current() }.unwrap();
     gl::load_with(|symbol| gl_window.get_proc_address(symbol) as *const _);
 
-    // Load a knot diagram from a .csv file
-    let path = Path::new("diagrams/legendrian.csv");
-    let mut knots = vec![
-        Diagram::from_path(path)
-            .unwrap()
-            .apply_move(CromwellMove::Stabilization {
-                cardinality: Cardinality::SW,
-                i: 3,
-                j: 2,
-            })
-            .unwrap()
-            .apply_move(CromwellMove::Translation(Direction::Left))
-            .unwrap()
-            .generate_knot(),
-        Diagram::from_path(path)
-            .unwrap()
-            .apply_move(CromwellMove::Stabilization {
-                cardinality: Cardinality::SE,
-                i: 3,
-                j: 2,
-            })
-            .unwrap()
-            .generate_knot(),
-        Diagram::from_path(path)
-            .unwrap()
-            .apply_move(CromwellMove::Stabilization {
-                cardinality: Cardinality::NW,
-                i: 3,
-                j: 2,
-            })
-            .unwrap()
-            .apply_move(CromwellMove::Translation(Direction::Up))
-            .unwrap()
-            .generate_knot(),
-    ];
+    // Load each of the knot diagrams specified on the command line
+    let paths = get_diagram_paths(std::env::args());
+    let mut knots = vec![];
+    for path in paths.iter() {
+        let diagram = match Diagram::from_path(path) {
+            Ok(diagram) => diagram,
+            Err(e) => {
+                eprintln!("Failed to load grid diagram `{}`: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        };
+        match diagram.generate_knot() {
+            Ok(knot) => knots.push(knot),
+            Err(e) => {
+                eprintln!("Failed to generate a knot from `{}`: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
 
     // Set up OpenGL shader programs for rendering
+    let load_shader = |path: &Path| match utils::load_file_as_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Failed to load shader `{}`: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
     let draw_program = Program::from_sources(
-        utils::load_file_as_string(Path::new("shaders/draw.vert")),
-        utils::load_file_as_string(Path::new("shaders/draw.frag")),
+        load_shader(Path::new("shaders/draw.vert")),
+        load_shader(Path::new("shaders/draw.frag")),
     )
     .unwrap();
+    utils::gl_check("Program::from_sources");
 
     // Interaction (mouse clicks, etc.)
     let mut interaction = InteractionState::new();
-
-    // Set up the model-view-projection (MVP) matrices
-    let mut models = vec![
-        Matrix4::from_translation(Vector3::new(-15.0, 0.0, 0.0)),
-        Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.0)),
-        Matrix4::from_translation(Vector3::new(15.0, 0.0, 0.0)),
-    ];
-    let view = Matrix4::look_at(
-        Point3::new(0.0, 0.0, 45.0),
-        Point3::origin(),
-        Vector3::unit_y(),
+    let mut recorder = utils::FrameRecorder::new(
+        Path::new(constants::FRAME_DIRECTORY),
+        constants::FRAME_STRIDE,
     );
+
+    // Set up the model-view-projection (MVP) matrices, pulling the camera back far enough
+    // to see every knot
+    let fov = std::f32::consts::FRAC_PI_4;
+    let mut models = layout_row(&knots, constants::LAYOUT_GAP);
+    let home_distance = get_framing_distance(&knots, &models, fov);
+    interaction.camera_distance = home_distance;
+    let view = get_view_matrix(interaction.camera_distance, interaction.camera_rotation);
     let projection = cgmath::perspective(
-        cgmath::Rad(std::f32::consts::FRAC_PI_4),
+        cgmath::Rad(fov),
         constants::WIDTH as f32 / constants::HEIGHT as f32,
         0.1,
         1000.0,
@@ -132,6 +240,9 @@ fn main() {
     draw_program.uniform_matrix_4f("u_view", &view);
     draw_program.uniform_matrix_4f("u_projection", &projection);
 
+    // Used to advance the physics simulation at the same rate, regardless of the frame rate
+    let mut last_frame = std::time::Instant::now();
+
     loop {
         events_loop.poll_events(|event| match event {
             glutin::Event::WindowEvent { event, .. } => match event {
@@ -144,16 +255,29 @@ fn main() {
                     interaction.cursor_curr.y = position.1 as f32 / constants::HEIGHT as f32;
 
                     if interaction.lmouse_pressed {
-                        let delta = interaction.get_mouse_delta() * constants::MOUSE_SENSITIVITY;
+                        // Orbit the camera around the origin
+                        let rotation =
+                            get_arcball_rotation(interaction.cursor_prev, interaction.cursor_curr);
+                        interaction.camera_rotation = rotation * interaction.camera_rotation;
 
-                        let rot_xz = Matrix4::from_angle_y(cgmath::Rad(delta.x));
-                        let rot_yz = Matrix4::from_angle_x(cgmath::Rad(delta.y));
-
-                        for model in models.iter_mut() {
-                            *model = rot_xz * rot_yz * *model;
-                        }
+                        let view = get_view_matrix(
+                            interaction.camera_distance,
+                            interaction.camera_rotation,
+                        );
+                        draw_program.uniform_matrix_4f("u_view", &view);
                     }
                 }
+                glutin::WindowEvent::MouseWheel { delta, .. } => {
+                    let amount = match delta {
+                        glutin::MouseScrollDelta::LineDelta(_, y) => y as f32,
+                        glutin::MouseScrollDelta::PixelDelta(_, y) => y as f32 * 0.1,
+                    };
+                    interaction.zoom(amount);
+
+                    let view =
+                        get_view_matrix(interaction.camera_distance, interaction.camera_rotation);
+                    draw_program.uniform_matrix_4f("u_view", &view);
+                }
                 glutin::WindowEvent::MouseInput { state, button, .. } => match button {
                     glutin::MouseButton::Left => {
                         if let glutin::ElementState::Pressed = state {
@@ -166,6 +290,37 @@ fn main() {
                     glutin::MouseButton::Right => {
                         if let glutin::ElementState::Pressed = state {
                             interaction.rmouse_pressed = true;
+
+                            // Pin (or un-pin) the bead (if any) under the cursor: only the
+                            // knot nearest to the camera is affected when several are hit
+                            let view = get_view_matrix(
+                                interaction.camera_distance,
+                                interaction.camera_rotation,
+                            );
+                            let nearest = knots
+                                .iter()
+                                .zip(models.iter())
+                                .enumerate()
+                                .filter_map(|(index, (knot, model))| {
+                                    let mvp = projection * view * model;
+                                    let (origin, direction) =
+                                        get_cursor_ray(interaction.cursor_curr, &mvp);
+                                    knot.pick(&origin, &direction)
+                                        .map(|(bead, t)| (index, bead, t))
+                                })
+                                .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+
+                            if let Some((index, bead, _)) = nearest {
+                                let knot = &mut knots[index];
+                                let stuck = !knot.is_stuck(bead);
+                                knot.set_stuck(bead, stuck);
+                                println!(
+                                    "{} bead #{} on knot #{}",
+                                    if stuck { "Pinned" } else { "Released" },
+                                    bead,
+                                    index
+                                );
+                            }
                         } else {
                             interaction.rmouse_pressed = false;
                         }
@@ -176,6 +331,15 @@ fn main() {
                     if let Some(key) = input.virtual_keycode {
                         match input.state {
                             glutin::ElementState::Pressed => match key {
+                                glutin::VirtualKeyCode::Space => {
+                                    interaction.paused = !interaction.paused;
+                                }
+                                glutin::VirtualKeyCode::N => {
+                                    interaction.step_requested = true;
+                                }
+                                glutin::VirtualKeyCode::C => {
+                                    interaction.recenter = !interaction.recenter;
+                                }
                                 glutin::VirtualKeyCode::R => {
                                     for knot in knots.iter_mut() {
                                         knot.reset();
@@ -183,7 +347,26 @@ fn main() {
                                 }
                                 glutin::VirtualKeyCode::S => {
                                     let path = Path::new("frame.png");
-                                    utils::save_frame(path, constants::WIDTH, constants::HEIGHT);
+                                    utils::save_frame(
+                                        path,
+                                        constants::WIDTH,
+                                        constants::HEIGHT,
+                                        false,
+                                    );
+                                }
+                                glutin::VirtualKeyCode::V => {
+                                    if recorder.is_active() {
+                                        recorder.stop();
+                                        println!("Stopped recording frames");
+                                    } else {
+                                        match recorder.start() {
+                                            Ok(()) => println!(
+                                                "Recording frames to `{}`",
+                                                constants::FRAME_DIRECTORY
+                                            ),
+                                            Err(e) => println!("Failed to start recording: {}", e),
+                                        }
+                                    }
                                 }
                                 glutin::VirtualKeyCode::F => unsafe {
                                     gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
@@ -191,14 +374,65 @@ fn main() {
                                 glutin::VirtualKeyCode::W => unsafe {
                                     gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
                                 },
+                                glutin::VirtualKeyCode::Tab => {
+                                    interaction.focused = (interaction.focused + 1) % knots.len();
+                                    println!("Editing knot #{}", interaction.focused);
+                                }
+                                glutin::VirtualKeyCode::Key0 => {
+                                    let index = interaction.focused;
+                                    let undone = match knots[index].get_diagram_mut() {
+                                        Some(diagram) => diagram.undo().map(|_| ()),
+                                        None => Err("This knot has no grid diagram"),
+                                    };
+                                    match undone.and_then(|_| knots[index].regenerate()) {
+                                        Ok(()) => {
+                                            println!("Undid the last move on knot #{}", index);
+                                            models = layout_row(&knots, constants::LAYOUT_GAP);
+                                        }
+                                        Err(e) => {
+                                            println!("Failed to undo on knot #{}: {}", index, e)
+                                        }
+                                    }
+                                }
                                 glutin::VirtualKeyCode::H => {
-                                    models = vec![
-                                        Matrix4::from_translation(Vector3::new(-15.0, 0.0, 0.0)),
-                                        Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.0)),
-                                        Matrix4::from_translation(Vector3::new(15.0, 0.0, 0.0)),
-                                    ];
+                                    interaction.camera_distance = home_distance;
+                                    interaction.camera_rotation = Quaternion::one();
+
+                                    let view = get_view_matrix(
+                                        interaction.camera_distance,
+                                        interaction.camera_rotation,
+                                    );
+                                    draw_program.uniform_matrix_4f("u_view", &view);
+                                }
+                                _ => {
+                                    // Apply the Cromwell move (if any) bound to this key to the
+                                    // focused knot's grid diagram, then regenerate the knot,
+                                    // which will start relaxing from scratch
+                                    let index = interaction.focused;
+                                    let cromwell = knots[index]
+                                        .get_diagram()
+                                        .and_then(|diagram| get_cromwell_move(key, diagram));
+                                    if let Some(cromwell) = cromwell {
+                                        let applied = knots[index]
+                                            .get_diagram_mut()
+                                            .unwrap()
+                                            .apply_move(cromwell)
+                                            .map(|_| ());
+                                        match applied.and_then(|_| knots[index].regenerate()) {
+                                            Ok(()) => {
+                                                println!(
+                                                    "Applied {:?} to knot #{}",
+                                                    cromwell, index
+                                                );
+                                                models = layout_row(&knots, constants::LAYOUT_GAP);
+                                            }
+                                            Err(e) => println!(
+                                                "Failed to apply {:?} to knot #{}: {}",
+                                                cromwell, index, e
+                                            ),
+                                        }
+                                    }
                                 }
-                                _ => (),
                             },
                             // Key released...
                             _ => (),
@@ -213,13 +447,120 @@ fn main() {
 
         draw_program.uniform_2f("u_mouse", &interaction.cursor_curr);
 
-        // Relax each knot and draw it
+        // Measure the time since the last frame in units of simulation steps (a long pause,
+        // e.g. while the window is being dragged, shouldn't cause the simulation to jump)
+        let now = std::time::Instant::now();
+        let elapsed = (now - last_frame).as_secs_f32() * constants::STEPS_PER_SECOND;
+        last_frame = now;
+
+        // Relax each knot (unless the simulation is paused) and draw it: single steps requested
+        // while the simulation is paused always advance it by exactly one step
+        let step = interaction.should_step();
+        let dt = if interaction.paused {
+            1.0
+        } else {
+            elapsed.min(constants::MAX_TIME_STEP)
+        };
+        let view = get_view_matrix(interaction.camera_distance, interaction.camera_rotation);
+        let frustum = Frustum::new(&(projection * view));
         for (knot, model) in knots.iter_mut().zip(models.iter()) {
             draw_program.uniform_matrix_4f("u_model", model);
-            knot.relax();
-            knot.draw(true);
+            if step {
+                knot.relax(dt);
+                if interaction.recenter {
+                    knot.recenter();
+                }
+            }
+
+            // Knots that are entirely off-screen still relax, but aren't drawn
+            if knot.is_visible(&frustum, model) {
+                knot.draw(&draw_program, true);
+            }
         }
+        utils::gl_check("drawing knots");
+        recorder.record(constants::WIDTH, constants::HEIGHT);
 
         gl_window.swap_buffers().unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts `args` to owned strings, like the ones returned by `std::env::args`.
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn diagram_paths_skip_the_program_name() {
+        assert_eq!(
+            get_diagram_paths(to_args(&["knots"])),
+            vec![PathBuf::from("diagrams/legendrian.csv")]
+        );
+        assert_eq!(
+            get_diagram_paths(to_args(&[
+                "knots",
+                "diagrams/trefoil.csv",
+                "diagrams/figure_eight.csv"
+            ])),
+            vec![
+                PathBuf::from("diagrams/trefoil.csv"),
+                PathBuf::from("diagrams/figure_eight.csv")
+            ]
+        );
+    }
+
+    #[test]
+    fn layout_row_separates_the_bounding_spheres() {
+        let knots: Vec<Knot> = ["trefoil", "figure_eight"]
+            .iter()
+            .map(|name| {
+                let path = format!("diagrams/{}.csv", name);
+                let diagram = Diagram::from_path(Path::new(&path)).unwrap();
+                diagram.generate_knot().unwrap()
+            })
+            .collect();
+        let models = layout_row(&knots, 1.0);
+        assert_eq!(models.len(), 2);
+
+        let spheres: Vec<(Point3<f32>, f32)> = knots
+            .iter()
+            .zip(models.iter())
+            .map(|(knot, model)| {
+                let (center, radius) = knot.bounding_sphere();
+                (model.transform_point(Point3::from_vec(center)), radius)
+            })
+            .collect();
+        let ((a, r_a), (b, r_b)) = (spheres[0], spheres[1]);
+
+        // The spheres sit on the x-axis, `gap` units apart, and the row is centered
+        assert!(a.y.abs() < constants::EPSILON && a.z.abs() < constants::EPSILON);
+        assert!(((b.x - r_b) - (a.x + r_a) - 1.0).abs() < constants::EPSILON);
+        assert!(((a.x - r_a) + (b.x + r_b)).abs() < constants::EPSILON);
+    }
+
+    #[test]
+    fn number_keys_choose_cromwell_moves() {
+        let diagram = Diagram::from_path(Path::new("diagrams/trefoil.csv")).unwrap();
+        let get_move = |key| get_cromwell_move(key, &diagram);
+
+        match get_move(glutin::VirtualKeyCode::Key1) {
+            Some(CromwellMove::Translation(Direction::Up)) => (),
+            other => panic!("Unexpected move: {:?}", other),
+        }
+        match get_move(glutin::VirtualKeyCode::Key6) {
+            Some(CromwellMove::Commutation {
+                axis: Axis::Column,
+                start_index: 0,
+            }) => (),
+            other => panic!("Unexpected move: {:?}", other),
+        }
+
+        // The stabilization targets the `x` in the first row, so it can always be applied
+        let stabilization = get_move(glutin::VirtualKeyCode::Key7).unwrap();
+        assert!(diagram.can_apply(&stabilization));
+        assert!(get_move(glutin::VirtualKeyCode::Key9).is_none());
+    }
+}
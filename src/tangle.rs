@@ -1,3 +1,6 @@
+use crate::diagram::Diagram;
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Tangle {
     // Two vertical strands
     Infinity,
@@ -7,6 +10,15 @@ pub enum Tangle {
 
     // Two twisted strands (left-handed or right-handed)
     N(isize),
+
+    // Two strands twisted vertically (the reflection of `N`)
+    Vertical(isize),
+
+    // Two tangles placed side-by-side, with their adjacent endpoints connected
+    Sum(Box<Tangle>, Box<Tangle>),
+
+    // Two tangles placed one above the other, with their adjacent endpoints connected
+    Product(Box<Tangle>, Box<Tangle>),
 }
 
 pub enum PointOfCrossing {
@@ -17,25 +29,298 @@ pub enum PointOfCrossing {
 }
 
 impl Tangle {
-    /// Returns `true` if this is a rational tangle and `false` otherwise.
+    /// Returns `true` if this is a rational tangle and `false` otherwise. A rational tangle
+    /// is built from twists by repeatedly adding horizontal twists to its side or vertical
+    /// twists below it.
     pub fn is_rational(&self) -> bool {
-        unimplemented!()
+        match self {
+            Tangle::Sum(a, b) => {
+                (a.is_rational() && b.is_integral()) || (a.is_integral() && b.is_rational())
+            }
+            Tangle::Product(a, b) => {
+                (a.is_rational() && b.is_vertical()) || (a.is_vertical() && b.is_rational())
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns `true` if this tangle is made up of horizontal twists only.
+    fn is_integral(&self) -> bool {
+        match self {
+            Tangle::Zero | Tangle::N(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if this tangle is made up of vertical twists only.
+    fn is_vertical(&self) -> bool {
+        match self {
+            Tangle::Infinity | Tangle::Vertical(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Renders this tangle as an operand of a sum or product, adding parentheses if needed.
+    fn as_operand(&self) -> String {
+        match self {
+            Tangle::Vertical(_) | Tangle::Sum(..) | Tangle::Product(..) => format!("({})", self),
+            _ => format!("{}", self),
+        }
+    }
+
+    /// Returns the fraction `p / q` associated with this tangle, reduced to lowest terms with
+    /// a non-negative denominator (`Infinity` corresponds to `(1, 0)`), or `None` if this is
+    /// not a rational tangle. The fraction is obtained by folding the twists into a continued
+    /// fraction: sums add fractions and products add their reciprocals.
+    pub fn fraction(&self) -> Option<(isize, isize)> {
+        if !self.is_rational() {
+            return None;
+        }
+
+        let (p, q) = match self {
+            Tangle::Infinity => (1, 0),
+            Tangle::Zero => (0, 1),
+            Tangle::N(k) => (*k, 1),
+            Tangle::Vertical(k) => (1, *k),
+            Tangle::Sum(a, b) => {
+                let (p_a, q_a) = a.fraction()?;
+                let (p_b, q_b) = b.fraction()?;
+                (p_a * q_b + p_b * q_a, q_a * q_b)
+            }
+            Tangle::Product(a, b) => {
+                let (p_a, q_a) = a.fraction()?;
+                let (p_b, q_b) = b.fraction()?;
+                (p_a * p_b, p_a * q_b + p_b * q_a)
+            }
+        };
+
+        Some(reduce(p, q))
     }
 
     pub fn product(&self, other: &Tangle) -> Tangle {
-        unimplemented!()
+        Tangle::Product(Box::new(self.clone()), Box::new(other.clone()))
     }
 
     pub fn sum(&self, other: &Tangle) -> Tangle {
-        unimplemented!()
+        Tangle::Sum(Box::new(self.clone()), Box::new(other.clone()))
     }
 
+    /// Returns `true` if this tangle and `other` are equivalent. By Conway's theorem, two
+    /// rational tangles are equivalent if and only if their fractions are equal. Tangles that
+    /// are not rational are never considered equivalent.
     pub fn equivalent(&self, other: &Tangle) -> bool {
-        unimplemented!()
+        match (self.fraction(), other.fraction()) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
     }
 
-    /// Reflects this tangle across the NW-SE diagonal.
+    /// Connects the NW endpoint of this tangle to the NE endpoint and the SW endpoint to the
+    /// SE endpoint, producing a grid diagram of the resulting knot (or link). Only tangles
+    /// made up of a single run of twists can currently be closed.
+    pub fn numerator_closure(&self) -> Result<Diagram, String> {
+        match self {
+            // Two separate loops
+            Tangle::Zero => Diagram::from_data(unlink_grid()),
+            // A single loop
+            Tangle::Infinity => Diagram::from_data(unknot_grid()),
+            // The (2, k) torus knot (or link)
+            Tangle::N(k) => Diagram::from_data(torus_grid(*k)),
+            // A single loop with `k` kinks
+            Tangle::Vertical(_) => Diagram::from_data(unknot_grid()),
+            _ => Err(
+                "Only twist tangles can be closed into a grid diagram at the moment".to_string(),
+            ),
+        }
+    }
+
+    /// Connects the NW endpoint of this tangle to the SW endpoint and the NE endpoint to the
+    /// SE endpoint, producing a grid diagram of the resulting knot (or link). Only tangles
+    /// made up of a single run of twists can currently be closed.
+    pub fn denominator_closure(&self) -> Result<Diagram, String> {
+        match self {
+            // The denominator closure of a tangle is the numerator closure of its reflection
+            Tangle::Zero | Tangle::Infinity | Tangle::N(_) | Tangle::Vertical(_) => {
+                self.reflect().numerator_closure()
+            }
+            _ => Err(
+                "Only twist tangles can be closed into a grid diagram at the moment".to_string(),
+            ),
+        }
+    }
+
+    /// Reflects this tangle across the NW-SE diagonal. Horizontal twists become vertical
+    /// twists (and vice-versa) with their handedness negated, so `Zero` and `Infinity` are
+    /// exchanged. Reflecting a sum yields the product of the reflected summands, and
+    /// reflecting a product yields the sum of the reflected factors.
     pub fn reflect(&self) -> Tangle {
-        unimplemented!()
+        match self {
+            Tangle::Infinity => Tangle::Zero,
+            Tangle::Zero => Tangle::Infinity,
+            Tangle::N(k) => Tangle::Vertical(-k),
+            Tangle::Vertical(k) => Tangle::N(-k),
+            Tangle::Sum(a, b) => a.reflect().product(&b.reflect()),
+            Tangle::Product(a, b) => a.reflect().sum(&b.reflect()),
+        }
+    }
+}
+
+/// Reduces the fraction `p / q` to lowest terms, with a non-negative denominator.
+fn reduce(p: isize, q: isize) -> (isize, isize) {
+    let (mut a, mut b) = (p.abs(), q.abs());
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+
+    // Only `0 / 0` has a GCD of zero: leave it untouched
+    if a == 0 {
+        return (p, q);
+    }
+
+    if q < 0 {
+        (-p / a, -q / a)
+    } else {
+        (p / a, q / a)
+    }
+}
+
+impl std::fmt::Display for Tangle {
+    /// Renders this tangle in Conway's notation: integer tangles are written as integers,
+    /// vertical twists as `k 0`, and sums and products as `a + b` and `a * b` respectively
+    /// (with parentheses around compound operands).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tangle::Infinity => write!(f, "∞"),
+            Tangle::Zero => write!(f, "0"),
+            Tangle::N(k) => write!(f, "{}", k),
+            Tangle::Vertical(k) => write!(f, "{} 0", k),
+            Tangle::Sum(a, b) => write!(f, "{} + {}", a.as_operand(), b.as_operand()),
+            Tangle::Product(a, b) => write!(f, "{} * {}", a.as_operand(), b.as_operand()),
+        }
+    }
+}
+
+/// Returns the grid of a single, unknotted loop.
+fn unknot_grid() -> Vec<Vec<char>> {
+    vec![vec!['x', 'o'], vec!['o', 'x']]
+}
+
+/// Returns the grid of two separate, unknotted loops.
+fn unlink_grid() -> Vec<Vec<char>> {
+    vec![
+        vec!['x', 'o', ' ', ' '],
+        vec!['o', 'x', ' ', ' '],
+        vec![' ', ' ', 'x', 'o'],
+        vec![' ', ' ', 'o', 'x'],
+    ]
+}
+
+/// Returns the grid of the `(2, k)` torus knot (or link), which has `|k| + 2` rows and columns:
+/// each `x` lies on the main diagonal and each `o` lies two columns to its right (wrapping
+/// around). Negative values of `k` produce the mirror image.
+fn torus_grid(k: isize) -> Vec<Vec<char>> {
+    if k == 0 {
+        return unlink_grid();
+    }
+
+    let resolution = k.abs() as usize + 2;
+    let mut data = vec![vec![' '; resolution]; resolution];
+    for i in 0..resolution {
+        data[i][i] = 'x';
+        data[i][(i + 2) % resolution] = 'o';
+    }
+
+    if k < 0 {
+        for row in data.iter_mut() {
+            row.reverse();
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflect_exchanges_horizontal_and_vertical_twists() {
+        assert_eq!(Tangle::Zero.reflect(), Tangle::Infinity);
+        assert_eq!(Tangle::N(3).reflect(), Tangle::Vertical(-3));
+
+        // A sum is reflected into a product, and the fraction `p / q` becomes `-q / p`
+        let tangle = Tangle::Vertical(3).sum(&Tangle::N(2));
+        let reflected = tangle.reflect();
+        assert_eq!(reflected, Tangle::N(-3).product(&Tangle::Vertical(-2)));
+        assert_eq!(tangle.fraction(), Some((7, 3)));
+        assert_eq!(reflected.fraction(), Some((-3, 7)));
+
+        assert_eq!(reflected.reflect(), tangle);
+    }
+
+    #[test]
+    fn equivalent_tangles_have_equal_fractions() {
+        assert!(Tangle::N(1).equivalent(&Tangle::Vertical(1)));
+        assert!(Tangle::Vertical(3)
+            .sum(&Tangle::N(2))
+            .equivalent(&Tangle::N(2).sum(&Tangle::Vertical(3))));
+        assert!(!Tangle::Zero.equivalent(&Tangle::Infinity));
+        assert!(!Tangle::N(2).equivalent(&Tangle::N(-2)));
+
+        // Tangles that aren't rational are never equivalent, not even to themselves
+        let tangle = Tangle::Vertical(2).sum(&Tangle::Vertical(2));
+        assert_eq!(tangle.fraction(), None);
+        assert!(!tangle.equivalent(&tangle));
+    }
+
+    #[test]
+    fn closures_of_twist_tangles() {
+        let trefoil = Tangle::N(3).numerator_closure().unwrap();
+        assert_eq!(trefoil.writhe(), 3);
+        assert_eq!(trefoil.generate_knot().unwrap().identify(), Some("3_1"));
+        assert_eq!(Tangle::N(-3).numerator_closure().unwrap().writhe(), -3);
+
+        assert_eq!(
+            Tangle::Infinity
+                .numerator_closure()
+                .unwrap()
+                .components()
+                .len(),
+            1
+        );
+        assert_eq!(
+            Tangle::Zero.numerator_closure().unwrap().components().len(),
+            2
+        );
+
+        // The denominator closure is the numerator closure of the reflection
+        assert_eq!(
+            Tangle::Vertical(3)
+                .denominator_closure()
+                .unwrap()
+                .get_data(),
+            Tangle::N(-3).numerator_closure().unwrap().get_data()
+        );
+
+        assert!(Tangle::N(1).sum(&Tangle::N(2)).numerator_closure().is_err());
+        assert!(Tangle::N(1)
+            .sum(&Tangle::N(2))
+            .denominator_closure()
+            .is_err());
+    }
+
+    #[test]
+    fn display_uses_conway_notation() {
+        assert_eq!(Tangle::Infinity.to_string(), "∞");
+        assert_eq!(Tangle::Zero.to_string(), "0");
+        assert_eq!(Tangle::N(-2).to_string(), "-2");
+        assert_eq!(Tangle::Vertical(3).to_string(), "3 0");
+
+        let tangle = Tangle::Vertical(3)
+            .sum(&Tangle::N(2))
+            .product(&Tangle::Vertical(1));
+        assert_eq!(tangle.to_string(), "((3 0) + 2) * (1 0)");
     }
 }
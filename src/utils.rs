@@ -1,12 +1,22 @@
+use cgmath::Vector3;
 use core::ffi::c_void;
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use gl::types::GLenum;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
-/// A helper function for taking screenshots
-pub fn save_frame(path: &Path, width: u32, height: u32) {
-    let mut pixels: Vec<u8> = Vec::new();
-    pixels.reserve((width * height * 3) as usize);
+#[cfg(test)]
+use std::cell::{Cell, RefCell};
+
+/// A helper function for taking screenshots: if `alpha` is `true`, the alpha channel of
+/// the framebuffer is saved as well.
+pub fn save_frame(path: &Path, width: u32, height: u32, alpha: bool) {
+    let (channels, format, color_type) = if alpha {
+        (4, gl::RGBA, image::RGBA(8))
+    } else {
+        (3, gl::RGB, image::RGB(8))
+    };
+    let mut pixels = vec![0u8; frame_buffer_len(width, height, alpha)];
 
     unsafe {
         // We don't want any alignment padding on pixel rows.
@@ -16,22 +26,302 @@ pub fn save_frame(path: &Path, width: u32, height: u32) {
             0,
             width as i32,
             height as i32,
-            gl::RGB,
+            format,
             gl::UNSIGNED_BYTE,
             pixels.as_mut_ptr() as *mut c_void,
         );
-        pixels.set_len((width * height * 3) as usize);
+    }
+    gl_check("glReadPixels");
+
+    // OpenGL's origin is the bottom-left corner of the framebuffer, whereas images are
+    // stored from the top row down
+    let pixels = flip_rows(&pixels, (width * channels) as usize);
+
+    image::save_buffer(path, &pixels, width, height, color_type).unwrap();
+}
+
+/// Returns the number of bytes needed to hold a `width` x `height` frame, with three channels
+/// per pixel (RGB) or four if `alpha` is `true` (RGBA).
+fn frame_buffer_len(width: u32, height: u32, alpha: bool) -> usize {
+    let channels = if alpha { 4 } else { 3 };
+    width as usize * height as usize * channels
+}
+
+/// Drains OpenGL's error queue, printing every pending error along with `call`, which should
+/// name the GL call (or group of calls) that was just made. Errors are otherwise silent, so
+/// this should follow any GL call that might fail. This only does anything in debug builds.
+#[cfg(all(debug_assertions, not(test)))]
+pub fn gl_check(call: &str) {
+    loop {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            break;
+        }
+        eprintln!(
+            "OpenGL error after `{}`: {} (0x{:X})",
+            call,
+            gl_error_name(error),
+            error
+        );
+    }
+}
+
+#[cfg(all(not(debug_assertions), not(test)))]
+pub fn gl_check(_call: &str) {}
+
+#[cfg(test)]
+thread_local! {
+    // Tests don't have an OpenGL context, so errors are queued here in its place
+    static PENDING_GL_ERRORS: RefCell<Vec<GLenum>> = RefCell::new(vec![]);
+
+    // The last error that `gl_check` found (or `GL_NO_ERROR`)
+    static LAST_GL_ERROR: Cell<GLenum> = Cell::new(gl::NO_ERROR);
+}
+
+/// The test version of `gl_check`, which drains the errors queued with `push_gl_error`
+/// (instead of calling `glGetError`) and remembers the last one (see `last_gl_error`).
+#[cfg(test)]
+pub fn gl_check(call: &str) {
+    let errors: Vec<GLenum> =
+        PENDING_GL_ERRORS.with(|pending| pending.borrow_mut().drain(..).collect());
+    for error in errors {
+        eprintln!(
+            "OpenGL error after `{}`: {} (0x{:X})",
+            call,
+            gl_error_name(error),
+            error
+        );
+        LAST_GL_ERROR.with(|last| last.set(error));
+    }
+}
+
+/// Queues `error` to be found by the next call to `gl_check` (in tests only).
+#[cfg(test)]
+pub fn push_gl_error(error: GLenum) {
+    PENDING_GL_ERRORS.with(|pending| pending.borrow_mut().push(error));
+}
+
+/// Returns the last error found by `gl_check` on this thread (in tests only).
+#[cfg(test)]
+pub fn last_gl_error() -> GLenum {
+    LAST_GL_ERROR.with(|last| last.get())
+}
+
+/// Returns the name of the OpenGL error code `error`.
+#[cfg(any(debug_assertions, test))]
+fn gl_error_name(error: GLenum) -> &'static str {
+    match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        _ => "unknown error",
+    }
+}
+
+/// Records a numbered sequence of screenshots (`frame_0000.png`, `frame_0001.png`, ...) that
+/// can be assembled into an animation, e.g. of a knot relaxing.
+pub struct FrameRecorder {
+    // The directory that frames are saved to
+    directory: PathBuf,
+
+    // A frame is saved every `stride` calls to `record`
+    stride: usize,
+
+    // Whether or not frames are currently being saved
+    active: bool,
+
+    // The number of calls to `record` since recording started
+    calls: usize,
+
+    // The number of the next frame to be saved
+    next_frame: usize,
+}
+
+impl FrameRecorder {
+    pub fn new(directory: &Path, stride: usize) -> FrameRecorder {
+        FrameRecorder {
+            directory: directory.to_path_buf(),
+            stride: stride.max(1),
+            active: false,
+            calls: 0,
+            next_frame: 0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Starts recording, creating the output directory if it doesn't exist yet. Frame numbers
+    /// restart at zero, so any frames left over from a previous recording are overwritten.
+    pub fn start(&mut self) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        self.active = true;
+        self.calls = 0;
+        self.next_frame = 0;
+        Ok(())
     }
 
-    image::save_buffer(path, &pixels, width, height, image::RGB(8)).unwrap();
+    pub fn stop(&mut self) {
+        self.active = false;
+    }
+
+    /// Saves the current contents of the framebuffer as the next frame of the sequence, if
+    /// recording is active and this call falls on the recorder's stride. This should be
+    /// called once per iteration of the render loop, after everything has been drawn.
+    pub fn record(&mut self, width: u32, height: u32) {
+        if !self.active {
+            return;
+        }
+        if self.calls % self.stride == 0 {
+            let path = self.directory.join(frame_filename(self.next_frame));
+            save_frame(&path, width, height, false);
+            self.next_frame += 1;
+        }
+        self.calls += 1;
+    }
+}
+
+/// Returns the filename of frame number `index` in a recorded sequence, zero-padded to four
+/// digits so that the frames sort correctly.
+fn frame_filename(index: usize) -> String {
+    format!("frame_{:04}.png", index)
+}
+
+/// Reverses the order of the rows in a tightly packed image buffer, where each row is
+/// `row_length` bytes long.
+fn flip_rows(pixels: &[u8], row_length: usize) -> Vec<u8> {
+    pixels
+        .chunks(row_length)
+        .rev()
+        .flat_map(|row| row.iter().cloned())
+        .collect()
 }
 
 /// Returns the string contents of the file at `path`
-pub fn load_file_as_string(path: &Path) -> String {
-    let mut file = File::open(path).expect("File not found");
+pub fn load_file_as_string(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
     let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Something went wrong reading the file");
+    file.read_to_string(&mut contents)?;
 
-    contents
+    Ok(contents)
+}
+
+/// Returns `true` if `a` and `b` differ by at most `eps` and `false` otherwise.
+pub fn approx_eq(a: f32, b: f32, eps: f32) -> bool {
+    (a - b).abs() <= eps
+}
+
+/// Returns `true` if each component of `a` is within `eps` of the corresponding component
+/// of `b` and `false` otherwise.
+pub fn approx_eq_vec3(a: &Vector3<f32>, b: &Vector3<f32>, eps: f32) -> bool {
+    approx_eq(a.x, b.x, eps) && approx_eq(a.y, b.y, eps) && approx_eq(a.z, b.z, eps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gl_check_records_the_last_error() {
+        assert_eq!(last_gl_error(), gl::NO_ERROR);
+        gl_check("glDrawArrays");
+        assert_eq!(last_gl_error(), gl::NO_ERROR);
+
+        push_gl_error(gl::INVALID_VALUE);
+        push_gl_error(gl::INVALID_ENUM);
+        gl_check("glDrawArrays");
+        assert_eq!(last_gl_error(), gl::INVALID_ENUM);
+        assert_eq!(gl_error_name(last_gl_error()), "GL_INVALID_ENUM");
+
+        // The queue is drained, so the next check finds nothing new
+        gl_check("glDrawArrays");
+        assert_eq!(last_gl_error(), gl::INVALID_ENUM);
+    }
+
+    #[test]
+    fn approx_eq_tolerance() {
+        assert!(approx_eq(1.0, 1.0, 0.0));
+        assert!(approx_eq(1.0, 1.0009, 0.001));
+        assert!(approx_eq(1.0009, 1.0, 0.001));
+        assert!(!approx_eq(1.0, 1.0011, 0.001));
+        assert!(!approx_eq(1.0011, 1.0, 0.001));
+    }
+
+    #[test]
+    fn approx_eq_vec3_checks_every_component() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        assert!(approx_eq_vec3(
+            &a,
+            &Vector3::new(1.0009, 1.9991, 3.0),
+            0.001
+        ));
+        assert!(!approx_eq_vec3(&a, &Vector3::new(1.0011, 2.0, 3.0), 0.001));
+        assert!(!approx_eq_vec3(&a, &Vector3::new(1.0, 2.0011, 3.0), 0.001));
+        assert!(!approx_eq_vec3(&a, &Vector3::new(1.0, 2.0, 2.9989), 0.001));
+    }
+
+    #[test]
+    fn flip_rows_reverses_the_rows() {
+        // An RGB image that is 2 pixels wide and 3 pixels tall, so each row is 6 bytes long
+        let pixels = [
+            1, 2, 3, 4, 5, 6, //
+            7, 8, 9, 10, 11, 12, //
+            13, 14, 15, 16, 17, 18,
+        ];
+        assert_eq!(
+            flip_rows(&pixels, 6),
+            vec![13, 14, 15, 16, 17, 18, 7, 8, 9, 10, 11, 12, 1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(flip_rows(&flip_rows(&pixels, 6), 6), pixels.to_vec());
+    }
+
+    #[test]
+    fn flip_rows_keeps_rgba_pixels_intact() {
+        // An RGBA image that is 1 pixel wide and 2 pixels tall
+        let pixels = [1, 2, 3, 255, 4, 5, 6, 128];
+        assert_eq!(flip_rows(&pixels, 4), vec![4, 5, 6, 128, 1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn frame_buffer_len_counts_every_channel() {
+        assert_eq!(frame_buffer_len(4, 3, false), 4 * 3 * 3);
+        assert_eq!(frame_buffer_len(4, 3, true), 4 * 3 * 4);
+        assert_eq!(frame_buffer_len(0, 3, true), 0);
+    }
+
+    #[test]
+    fn load_file_as_string_reports_missing_files() {
+        let contents = load_file_as_string(Path::new("shaders/draw.frag")).unwrap();
+        assert!(contents.contains("u_base_color"));
+
+        let error = load_file_as_string(Path::new("shaders/missing.frag")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn frame_recorder_creates_its_directory() {
+        assert_eq!(frame_filename(7), "frame_0007.png");
+        assert_eq!(frame_filename(12345), "frame_12345.png");
+
+        let directory = std::env::temp_dir().join("knots_tests").join("frames");
+        let _ = fs::remove_dir_all(&directory);
+
+        let mut recorder = FrameRecorder::new(&directory, 0);
+        assert_eq!(recorder.stride, 1);
+        assert!(!recorder.is_active());
+
+        // Recording is a no-op until the recorder is started
+        recorder.record(4, 4);
+        assert_eq!(recorder.calls, 0);
+        assert!(!directory.exists());
+
+        recorder.start().unwrap();
+        assert!(recorder.is_active());
+        assert!(directory.is_dir());
+        recorder.stop();
+        assert!(!recorder.is_active());
+    }
 }